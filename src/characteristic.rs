@@ -0,0 +1,232 @@
+use crate::resource_util::{get_text, Language, TextTable, TextTables};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// IV order index of each stat in the order characteristics check them: HP/Atk/Def/Spe/SpA/SpD.
+const CHARACTERISTIC_STAT_INDEXES: [usize; 6] = [0, 1, 2, 5, 3, 4];
+
+/// Summary screen text hinting at a Pokemon's highest IV. Variants are grouped by stat in the
+/// order HP/Atk/Def/Spe/SpA/SpD, five to a stat, with the highest IV mod 5 picking one.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Characteristic {
+    LovesToEat,
+    TakesPlentyOfSiestas,
+    NodsOffALot,
+    ScattersThingsOften,
+    LikesToRelax,
+    ProudOfItsPower,
+    LikesToThrashAbout,
+    ALittleQuickTempered,
+    LikesToFight,
+    QuickTempered,
+    SturdyBody,
+    CapableOfTakingHits,
+    HighlyPersistent,
+    GoodEndurance,
+    GoodPerseverance,
+    LikesToRun,
+    AlertToSounds,
+    ImpetuousAndSilly,
+    SomewhatOfAClown,
+    QuickToFlee,
+    HighlyCurious,
+    Mischievous,
+    ThoroughlyCunning,
+    OftenLostInThought,
+    VeryFinicky,
+    StrongWilled,
+    SomewhatVain,
+    StronglyDefiant,
+    HatesToLose,
+    SomewhatStubborn,
+}
+
+impl Characteristic {
+    pub const ALL: [Characteristic; 30] = [
+        Characteristic::LovesToEat,
+        Characteristic::TakesPlentyOfSiestas,
+        Characteristic::NodsOffALot,
+        Characteristic::ScattersThingsOften,
+        Characteristic::LikesToRelax,
+        Characteristic::ProudOfItsPower,
+        Characteristic::LikesToThrashAbout,
+        Characteristic::ALittleQuickTempered,
+        Characteristic::LikesToFight,
+        Characteristic::QuickTempered,
+        Characteristic::SturdyBody,
+        Characteristic::CapableOfTakingHits,
+        Characteristic::HighlyPersistent,
+        Characteristic::GoodEndurance,
+        Characteristic::GoodPerseverance,
+        Characteristic::LikesToRun,
+        Characteristic::AlertToSounds,
+        Characteristic::ImpetuousAndSilly,
+        Characteristic::SomewhatOfAClown,
+        Characteristic::QuickToFlee,
+        Characteristic::HighlyCurious,
+        Characteristic::Mischievous,
+        Characteristic::ThoroughlyCunning,
+        Characteristic::OftenLostInThought,
+        Characteristic::VeryFinicky,
+        Characteristic::StrongWilled,
+        Characteristic::SomewhatVain,
+        Characteristic::StronglyDefiant,
+        Characteristic::HatesToLose,
+        Characteristic::SomewhatStubborn,
+    ];
+
+    /// Picks the characteristic from the highest IV. Ties are broken by checking stats starting
+    /// from `ec % 6` and wrapping around, keeping the first highest one found.
+    pub fn from_ec_and_ivs(ec: u32, ivs: &[u8; 6]) -> Characteristic {
+        let start = (ec % 6) as usize;
+        let mut stat = start;
+        for offset in 1..6 {
+            let candidate = (start + offset) % 6;
+            if ivs[CHARACTERISTIC_STAT_INDEXES[candidate]] > ivs[CHARACTERISTIC_STAT_INDEXES[stat]]
+            {
+                stat = candidate;
+            }
+        }
+
+        let iv = ivs[CHARACTERISTIC_STAT_INDEXES[stat]];
+        Characteristic::ALL[stat * 5 + (iv % 5) as usize]
+    }
+
+    /// IV order index of the stat this characteristic points to as the highest.
+    pub fn stat_index(&self) -> usize {
+        CHARACTERISTIC_STAT_INDEXES[*self as usize / 5]
+    }
+
+    /// Possible values of the highest IV, which all share the same remainder mod 5.
+    pub fn possible_ivs(&self) -> impl Iterator<Item = u8> {
+        ((*self as u8 % 5)..32).step_by(5)
+    }
+
+    /// English name.
+    pub fn name(&self) -> &'static str {
+        get_text(
+            TextTable::Characteristics,
+            Language::English,
+            *self as usize,
+        )
+        .unwrap_or_default()
+    }
+
+    pub fn localized_name<'a>(&self, text: &'a TextTables) -> &'a str {
+        text.get(TextTable::Characteristics, *self as usize)
+            .unwrap_or_default()
+    }
+}
+
+impl Display for Characteristic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Characteristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(characteristic) = s
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| Characteristic::ALL.get(i))
+        {
+            return Ok(*characteristic);
+        }
+
+        let name = s.replace(['-', '_', ' ', '.'], "").to_lowercase();
+        Characteristic::ALL
+            .into_iter()
+            .find(|c| c.name().replace(' ', "").to_lowercase() == name)
+            .ok_or_else(|| format!("Invalid characteristic \"{}\"", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characteristic_comes_from_the_highest_iv() {
+        // (ec, ivs in HP/Atk/Def/SpA/SpD/Spe order, characteristic)
+        for (ec, ivs, characteristic) in [
+            (0, [0, 0, 0, 0, 0, 25], Characteristic::LikesToRun),
+            (5, [0, 0, 0, 0, 0, 25], Characteristic::LikesToRun),
+            (1, [3, 12, 29, 4, 0, 7], Characteristic::GoodPerseverance),
+            (2, [31, 0, 0, 0, 0, 0], Characteristic::TakesPlentyOfSiestas),
+            (3, [0, 0, 0, 0, 18, 0], Characteristic::HatesToLose),
+        ] {
+            assert_eq!(
+                Characteristic::from_ec_and_ivs(ec, &ivs),
+                characteristic,
+                "EC {:08X} IVs {:?}",
+                ec,
+                ivs
+            );
+        }
+    }
+
+    #[test]
+    fn ties_start_from_ec_mod_6_and_wrap() {
+        // Stats are checked HP/Atk/Def/Spe/SpA/SpD starting at ec % 6.
+        let ivs = [31; 6];
+        for (ec, characteristic) in [
+            (0, Characteristic::TakesPlentyOfSiestas),
+            (1, Characteristic::LikesToThrashAbout),
+            (2, Characteristic::CapableOfTakingHits),
+            (3, Characteristic::AlertToSounds),
+            (4, Characteristic::Mischievous),
+            (5, Characteristic::SomewhatVain),
+            (0xFFFFFFFF, Characteristic::AlertToSounds),
+        ] {
+            assert_eq!(Characteristic::from_ec_and_ivs(ec, &ivs), characteristic);
+        }
+
+        // Atk and SpA tie, so whichever comes first from ec % 6 wins.
+        let ivs = [0, 20, 0, 20, 0, 0];
+        for (ec, characteristic) in [
+            (0, Characteristic::ProudOfItsPower),
+            (1, Characteristic::ProudOfItsPower),
+            (2, Characteristic::HighlyCurious),
+            (3, Characteristic::HighlyCurious),
+            (4, Characteristic::HighlyCurious),
+            (5, Characteristic::ProudOfItsPower),
+        ] {
+            assert_eq!(
+                Characteristic::from_ec_and_ivs(ec, &ivs),
+                characteristic,
+                "EC {}",
+                ec
+            );
+        }
+    }
+
+    #[test]
+    fn characteristics_parse_and_point_to_their_stat() {
+        assert_eq!("Loves to eat".parse(), Ok(Characteristic::LovesToEat));
+        assert_eq!("quick-to-flee".parse(), Ok(Characteristic::QuickToFlee));
+        assert_eq!("29".parse(), Ok(Characteristic::SomewhatStubborn));
+        assert!("30".parse::<Characteristic>().is_err());
+        for (i, characteristic) in Characteristic::ALL.into_iter().enumerate() {
+            assert_eq!(characteristic.name().parse(), Ok(characteristic));
+            assert_eq!(characteristic as usize, i);
+        }
+
+        assert_eq!(Characteristic::LikesToRun.stat_index(), 5);
+        assert_eq!(Characteristic::HighlyCurious.stat_index(), 3);
+        assert_eq!(
+            Characteristic::LikesToRun
+                .possible_ivs()
+                .collect::<Vec<u8>>(),
+            [0, 5, 10, 15, 20, 25, 30]
+        );
+        assert_eq!(
+            Characteristic::SomewhatStubborn
+                .possible_ivs()
+                .collect::<Vec<u8>>(),
+            [4, 9, 14, 19, 24, 29]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{MoveId, RoomType, Species};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorError {
+    /// A story flag outside of 1-6.
+    InvalidStoryFlag(u8),
+    /// A story flag name that does not match any milestone.
+    UnknownStoryFlag(String),
+    /// A type ID outside of 0-17, other than `-1` for no type.
+    InvalidType(i8),
+    /// A nature ID outside of 0-24.
+    InvalidNature(u8),
+    /// A species number that is not in the BDSP personal table.
+    InvalidSpecies(Species),
+    /// A level outside of 1-100.
+    InvalidLevel(u8),
+    /// An EV above 252.
+    InvalidEv(u8),
+    /// An egg move filter asks for a move none of the filtered species can have.
+    InvalidEggMove {
+        egg_move: MoveId,
+        species: Vec<Species>,
+        valid_moves: Vec<MoveId>,
+    },
+    /// The room has no row in one of the embedded tables.
+    MissingRoom { room: RoomType, table: &'static str },
+    /// The species has no row in one of the embedded tables.
+    MissingSpecies {
+        species: Species,
+        table: &'static str,
+    },
+    /// The story flag has no row in one of the embedded tables.
+    MissingStoryFlag { story_flag: u8, table: &'static str },
+    /// The species has no spawn rate for the story flag in `UgPokemonData`.
+    MissingFlagRate { species: Species, story_flag: u8 },
+    /// No Pokemon can spawn in the room for the given version and story flag.
+    NoEncounters { room: RoomType },
+    /// The last advance of a search, `initial_advance + advances`, does not fit in a `u32`.
+    AdvanceOverflow { initial_advance: u32, advances: u32 },
+    /// A filter expression could not be parsed. `column` is 1-based and counts characters.
+    FilterParse { column: usize, message: String },
+    /// An embedded or user supplied table could not be parsed.
+    TableParse {
+        table: &'static str,
+        message: String,
+    },
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorError::InvalidStoryFlag(flag) => {
+                write!(f, "Invalid story flag {}, expected a value from 1 to 6", flag)
+            }
+            GeneratorError::UnknownStoryFlag(name) => write!(
+                f,
+                "Invalid story flag \"{}\", expected 1-6 or one of underground-unlocked, strength, defog, seven-badges, waterfall, national-dex",
+                name
+            ),
+            GeneratorError::InvalidType(id) => {
+                write!(f, "Invalid type ID {}, expected a value from 0 to 17", id)
+            }
+            GeneratorError::InvalidNature(id) => {
+                write!(f, "Invalid nature ID {}, expected a value from 0 to 24", id)
+            }
+            GeneratorError::InvalidSpecies(species) => {
+                write!(f, "Species {} is not in the BDSP personal table", species)
+            }
+            GeneratorError::InvalidLevel(level) => {
+                write!(f, "Invalid level {}, expected a value from 1 to 100", level)
+            }
+            GeneratorError::InvalidEv(ev) => {
+                write!(f, "Invalid EV {}, expected a value from 0 to 252", ev)
+            }
+            GeneratorError::InvalidEggMove {
+                egg_move,
+                species,
+                valid_moves,
+            } => write!(
+                f,
+                "{} can never be the egg move of {}. Valid egg moves are: {}",
+                egg_move,
+                join(species),
+                join(valid_moves)
+            ),
+            GeneratorError::MissingRoom { room, table } => {
+                write!(f, "Room {:?} ({}) is missing from {}", room, *room as u8, table)
+            }
+            GeneratorError::MissingSpecies { species, table } => {
+                write!(f, "Species {} is missing from {}", species, table)
+            }
+            GeneratorError::MissingStoryFlag { story_flag, table } => {
+                write!(f, "Story flag {} is missing from {}", story_flag, table)
+            }
+            GeneratorError::MissingFlagRate {
+                species,
+                story_flag,
+            } => write!(
+                f,
+                "Species {} has no spawn rate for story flag {} in UgPokemonData",
+                species, story_flag
+            ),
+            GeneratorError::NoEncounters { room } => write!(
+                f,
+                "No Pokemon can spawn in room {:?} ({}) with this version and story flag",
+                room, *room as u8
+            ),
+            GeneratorError::AdvanceOverflow {
+                initial_advance,
+                advances,
+            } => write!(
+                f,
+                "Initial advance {} plus {} advances is past the last advance ({})",
+                initial_advance,
+                advances,
+                u32::MAX
+            ),
+            GeneratorError::FilterParse { column, message } => {
+                write!(f, "Invalid filter at column {}: {}", column, message)
+            }
+            GeneratorError::TableParse { table, message } => {
+                write!(f, "Failed to parse {}: {}", table, message)
+            }
+        }
+    }
+}
+
+impl Error for GeneratorError {}
+
+fn join<T: Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
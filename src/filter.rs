@@ -1,30 +1,184 @@
-use crate::{Advance, Pokemon};
+use crate::{
+    get_available_egg_moves, AbilityId, Advance, Characteristic, GeneratorError, ItemId, MoveId,
+    Nature, Pokemon, PokemonType, ShinyType, Species, Stat,
+};
+use std::str::FromStr;
 
-#[derive(Default, Debug)]
+/// An ability to filter for, either the slot the Pokemon rolled or the ability itself.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Ability {
+    /// Ability slot 0 or 1, as stored in `Pokemon::ability`.
+    Slot(u8),
+    Id(AbilityId),
+}
+
+impl Ability {
+    fn matches(&self, pokemon: &Pokemon) -> bool {
+        match self {
+            Ability::Slot(slot) => pokemon.ability == *slot,
+            Ability::Id(id) => pokemon.ability_id() == *id,
+        }
+    }
+}
+
+/// Parses slot `0` or `1`, or an English ability name. Other numbers are rejected rather than
+/// read as ability IDs, so a mistyped slot doesn't silently filter for an ability.
+impl FromStr for Ability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.parse::<u32>() {
+            Ok(0) => Ok(Ability::Slot(0)),
+            Ok(1) => Ok(Ability::Slot(1)),
+            Ok(_) => Err(format!(
+                "Invalid ability \"{}\", expected slot 0 or 1 or an ability name",
+                s
+            )),
+            Err(_) => s.parse().map(Ability::Id),
+        }
+    }
+}
+
+/// Criteria a single Pokemon has to meet. Unset fields match anything.
+#[derive(Debug)]
 pub struct Filter {
     pub shiny: bool,
-    pub species: Option<u16>,
+    pub shiny_type: Option<ShinyType>,
+    pub species: Option<Vec<Species>>,
+    /// Bounds on the unverified level, see [`GeneratorContext::levels`]. Pokemon without a
+    /// level never match.
+    ///
+    /// [`GeneratorContext::levels`]: crate::GeneratorContext::levels
+    pub min_level: Option<u8>,
+    pub max_level: Option<u8>,
     pub min_ivs: [u8; 6],
     pub max_ivs: [u8; 6],
-    pub ability: Option<u8>,
-    pub nature: Option<Vec<u8>>,
-    pub item: Option<u16>,
-    pub egg_move: Option<u16>,
-    pub gender: Option<u8>,
+    /// Bounds on how many IVs are 31.
+    pub min_perfect_ivs: Option<u8>,
+    pub max_perfect_ivs: Option<u8>,
+    /// Bounds on the sum of all six IVs.
+    pub min_iv_total: Option<u16>,
+    pub max_iv_total: Option<u16>,
+    /// Stats whose IV must be 0, such as Atk and Spe for Trick Room.
+    pub zero_ivs: Option<Vec<Stat>>,
+    /// Bounds on the stats at the Pokemon's level. Pokemon without a level never match.
+    pub min_stats: Option<[u16; 6]>,
+    pub max_stats: Option<[u16; 6]>,
+    pub ability: Option<Vec<Ability>>,
+    pub nature: Option<Vec<Nature>>,
+    pub hidden_power: Option<Vec<PokemonType>>,
+    pub characteristic: Option<Vec<Characteristic>>,
+    pub min_height: Option<u8>,
+    pub max_height: Option<u8>,
+    pub min_weight: Option<u8>,
+    pub max_weight: Option<u8>,
+    pub item: Option<Vec<ItemId>>,
+    pub egg_move: Option<Vec<MoveId>>,
+    /// Whether the Pokemon must have (`true`) or lack (`false`) an egg move.
+    pub has_egg_move: Option<bool>,
+    pub gender: Option<Vec<u8>>,
     pub exclusive: bool,
 }
 
+/// Something that can decide which Pokemon in an advance are of interest.
+pub trait PokemonFilter {
+    /// Reports filter settings that can never match, such as an egg move the filtered species
+    /// can't have.
+    fn validate(&self) -> Result<(), GeneratorError> {
+        Ok(())
+    }
+
+    fn check_pokemon(&self, pokemon: &Pokemon) -> bool;
+
+    /// Whether `apply` drops the Pokemon that fail `check_pokemon` instead of keeping the
+    /// whole advance.
+    fn exclusive(&self) -> bool;
+
+    /// Whether any Pokemon in the advance passes the filter.
+    fn passes_filter(&self, advance: &Advance) -> bool {
+        advance.pokemon().any(|p| self.check_pokemon(p))
+    }
+
+    /// Returns the advance if it passes the filter. In exclusive mode only the matching
+    /// Pokemon are kept.
+    fn apply(&self, mut advance: Advance) -> Option<Advance> {
+        if self.exclusive() {
+            advance.regular_pokemon.retain(|p| self.check_pokemon(p));
+            advance.rare_pokemon = advance.rare_pokemon.filter(|p| self.check_pokemon(p));
+            if !advance.regular_pokemon.is_empty() || advance.rare_pokemon.is_some() {
+                Some(advance)
+            } else {
+                None
+            }
+        } else if self.passes_filter(&advance) {
+            Some(advance)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            shiny: false,
+            shiny_type: None,
+            species: None,
+            min_level: None,
+            max_level: None,
+            min_ivs: [0; 6],
+            max_ivs: [31; 6],
+            min_perfect_ivs: None,
+            max_perfect_ivs: None,
+            min_iv_total: None,
+            max_iv_total: None,
+            zero_ivs: None,
+            min_stats: None,
+            max_stats: None,
+            ability: None,
+            nature: None,
+            hidden_power: None,
+            characteristic: None,
+            min_height: None,
+            max_height: None,
+            min_weight: None,
+            max_weight: None,
+            item: None,
+            egg_move: None,
+            has_egg_move: None,
+            gender: None,
+            exclusive: false,
+        }
+    }
+}
+
 impl Filter {
     pub fn shiny(mut self, shiny: bool) -> Filter {
         self.shiny = shiny;
         self
     }
 
-    pub fn species(mut self, species: u16) -> Filter {
+    pub fn shiny_type(mut self, shiny_type: ShinyType) -> Filter {
+        self.shiny_type = Some(shiny_type);
+        self
+    }
+
+    pub fn species(mut self, species: Vec<Species>) -> Filter {
         self.species = Some(species);
         self
     }
 
+    pub fn min_level(mut self, min_level: u8) -> Filter {
+        self.min_level = Some(min_level);
+        self
+    }
+
+    pub fn max_level(mut self, max_level: u8) -> Filter {
+        self.max_level = Some(max_level);
+        self
+    }
+
     pub fn min_ivs(mut self, min_ivs: [u8; 6]) -> Filter {
         self.min_ivs = min_ivs;
         self
@@ -35,58 +189,159 @@ impl Filter {
         self
     }
 
-    pub fn ability(mut self, ability: u8) -> Filter {
+    pub fn min_perfect_ivs(mut self, min_perfect_ivs: u8) -> Filter {
+        self.min_perfect_ivs = Some(min_perfect_ivs);
+        self
+    }
+
+    pub fn max_perfect_ivs(mut self, max_perfect_ivs: u8) -> Filter {
+        self.max_perfect_ivs = Some(max_perfect_ivs);
+        self
+    }
+
+    pub fn min_iv_total(mut self, min_iv_total: u16) -> Filter {
+        self.min_iv_total = Some(min_iv_total);
+        self
+    }
+
+    pub fn max_iv_total(mut self, max_iv_total: u16) -> Filter {
+        self.max_iv_total = Some(max_iv_total);
+        self
+    }
+
+    pub fn zero_ivs(mut self, zero_ivs: Vec<Stat>) -> Filter {
+        self.zero_ivs = Some(zero_ivs);
+        self
+    }
+
+    pub fn min_stats(mut self, min_stats: [u16; 6]) -> Filter {
+        self.min_stats = Some(min_stats);
+        self
+    }
+
+    pub fn max_stats(mut self, max_stats: [u16; 6]) -> Filter {
+        self.max_stats = Some(max_stats);
+        self
+    }
+
+    pub fn ability(mut self, ability: Vec<Ability>) -> Filter {
         self.ability = Some(ability);
         self
     }
 
-    pub fn nature(mut self, nature: Vec<u8>) -> Filter {
+    pub fn nature(mut self, nature: Vec<Nature>) -> Filter {
         self.nature = Some(nature);
         self
     }
 
-    pub fn item(mut self, item: u16) -> Filter {
+    pub fn hidden_power(mut self, hidden_power: Vec<PokemonType>) -> Filter {
+        self.hidden_power = Some(hidden_power);
+        self
+    }
+
+    pub fn characteristic(mut self, characteristic: Vec<Characteristic>) -> Filter {
+        self.characteristic = Some(characteristic);
+        self
+    }
+
+    pub fn min_height(mut self, min_height: u8) -> Filter {
+        self.min_height = Some(min_height);
+        self
+    }
+
+    pub fn max_height(mut self, max_height: u8) -> Filter {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    pub fn min_weight(mut self, min_weight: u8) -> Filter {
+        self.min_weight = Some(min_weight);
+        self
+    }
+
+    pub fn max_weight(mut self, max_weight: u8) -> Filter {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    pub fn item(mut self, item: Vec<ItemId>) -> Filter {
         self.item = Some(item);
         self
     }
 
-    pub fn egg_move(mut self, egg_move: u16) -> Filter {
+    pub fn egg_move(mut self, egg_move: Vec<MoveId>) -> Filter {
         self.egg_move = Some(egg_move);
         self
     }
 
-    pub fn gender(mut self, gender: u8) -> Filter {
+    pub fn has_egg_move(mut self, has_egg_move: bool) -> Filter {
+        self.has_egg_move = Some(has_egg_move);
+        self
+    }
+
+    pub fn gender(mut self, gender: Vec<u8>) -> Filter {
         self.gender = Some(gender);
         self
     }
+}
+
+impl PokemonFilter for Filter {
+    /// Checks the egg move filter against the egg moves the filtered species can have. Egg moves
+    /// are only checked when species are filtered too.
+    fn validate(&self) -> Result<(), GeneratorError> {
+        let (Some(species), Some(egg_moves)) = (&self.species, &self.egg_move) else {
+            return Ok(());
+        };
 
-    pub fn passes_filter(&self, advance: &Advance) -> bool {
-        for pokemon in advance.regular_pokemon.iter() {
-            if self.check_pokemon(pokemon) {
-                return true;
+        let mut valid_moves = Vec::new();
+        for s in species {
+            for egg_move in get_available_egg_moves(*s, 0)? {
+                if !valid_moves.contains(&egg_move) {
+                    valid_moves.push(egg_move);
+                }
             }
         }
+        valid_moves.sort();
+
+        match egg_moves.iter().find(|m| !valid_moves.contains(m)) {
+            Some(egg_move) => Err(GeneratorError::InvalidEggMove {
+                egg_move: *egg_move,
+                species: species.clone(),
+                valid_moves,
+            }),
+            None => Ok(()),
+        }
+    }
 
-        if let Some(pokemon) = &advance.rare_pokemon {
-            if self.check_pokemon(pokemon) {
-                return true;
+    fn check_pokemon(&self, pokemon: &Pokemon) -> bool {
+        if let Some(species) = &self.species {
+            if !species.contains(&pokemon.species) {
+                return false;
             }
         }
 
-        false
-    }
+        if let Some(min_level) = self.min_level {
+            if pokemon.level.is_none_or(|level| level < min_level) {
+                return false;
+            }
+        }
 
-    pub fn check_pokemon(&self, pokemon: &Pokemon) -> bool {
-        if let Some(species) = self.species {
-            if pokemon.species != species {
+        if let Some(max_level) = self.max_level {
+            if pokemon.level.is_none_or(|level| level > max_level) {
                 return false;
             }
         }
 
-        if self.shiny && !pokemon.shiny {
+        if self.shiny && !pokemon.shiny.is_shiny() {
             return false;
         }
 
+        if let Some(shiny_type) = self.shiny_type {
+            if pokemon.shiny != shiny_type {
+                return false;
+            }
+        }
+
         let mut passes_ivs = true;
         for (i, iv) in pokemon.ivs.iter().enumerate() {
             if !(self.min_ivs[i]..=self.max_ivs[i]).contains(iv) {
@@ -99,38 +354,222 @@ impl Filter {
             return false;
         }
 
-        if let Some(ability) = self.ability {
-            if pokemon.ability != ability {
+        if let Some(min_perfect_ivs) = self.min_perfect_ivs {
+            if pokemon.perfect_ivs() < min_perfect_ivs {
                 return false;
             }
         }
 
-        if let Some(nature) = &self.nature {
-            if !nature.contains(&pokemon.nature) {
+        if let Some(max_perfect_ivs) = self.max_perfect_ivs {
+            if pokemon.perfect_ivs() > max_perfect_ivs {
+                return false;
+            }
+        }
+
+        if let Some(min_iv_total) = self.min_iv_total {
+            if pokemon.iv_total() < min_iv_total {
                 return false;
             }
         }
 
-        if let Some(item) = self.item {
-            if pokemon.item != item {
+        if let Some(max_iv_total) = self.max_iv_total {
+            if pokemon.iv_total() > max_iv_total {
                 return false;
             }
         }
 
-        if let Some(egg_move) = self.egg_move {
-            if let Some(p_egg_move) = self.egg_move {
-                if egg_move != p_egg_move {
+        if let Some(zero_ivs) = &self.zero_ivs {
+            if zero_ivs.iter().any(|stat| pokemon.ivs[stat.index()] != 0) {
+                return false;
+            }
+        }
+
+        if self.min_stats.is_some() || self.max_stats.is_some() {
+            let Some(level) = pokemon.level else {
+                return false;
+            };
+            let stats = pokemon.stats(level);
+            let min_stats = self.min_stats.unwrap_or([0; 6]);
+            let max_stats = self.max_stats.unwrap_or([u16::MAX; 6]);
+            for (i, stat) in stats.iter().enumerate() {
+                if !(min_stats[i]..=max_stats[i]).contains(stat) {
                     return false;
                 }
             }
         }
 
-        if let Some(gender) = self.gender {
-            if pokemon.gender != gender {
+        if let Some(ability) = &self.ability {
+            if !ability.iter().any(|a| a.matches(pokemon)) {
+                return false;
+            }
+        }
+
+        if let Some(nature) = &self.nature {
+            if !nature.contains(&pokemon.nature) {
+                return false;
+            }
+        }
+
+        if let Some(hidden_power) = &self.hidden_power {
+            if !hidden_power.contains(&pokemon.hidden_power_type()) {
+                return false;
+            }
+        }
+
+        if let Some(characteristic) = &self.characteristic {
+            if !characteristic.contains(&pokemon.characteristic()) {
+                return false;
+            }
+        }
+
+        if let Some(min_height) = self.min_height {
+            if pokemon.height < min_height {
+                return false;
+            }
+        }
+
+        if let Some(max_height) = self.max_height {
+            if pokemon.height > max_height {
+                return false;
+            }
+        }
+
+        if let Some(min_weight) = self.min_weight {
+            if pokemon.weight < min_weight {
+                return false;
+            }
+        }
+
+        if let Some(max_weight) = self.max_weight {
+            if pokemon.weight > max_weight {
+                return false;
+            }
+        }
+
+        if let Some(item) = &self.item {
+            if !item.contains(&pokemon.item) {
+                return false;
+            }
+        }
+
+        if let Some(egg_move) = &self.egg_move {
+            if !pokemon.egg_move.is_some_and(|m| egg_move.contains(&m)) {
+                return false;
+            }
+        }
+
+        if let Some(has_egg_move) = self.has_egg_move {
+            if pokemon.egg_move.is_some() != has_egg_move {
+                return false;
+            }
+        }
+
+        if let Some(gender) = &self.gender {
+            if !gender.contains(&pokemon.gender) {
                 return false;
             }
         }
 
         true
     }
+
+    fn exclusive(&self) -> bool {
+        self.exclusive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIBLE: Species = Species(443);
+    const BAGON: Species = Species(371);
+    const DRAGON_DANCE: MoveId = MoveId(349);
+    const OUTRAGE: MoveId = MoveId(200);
+
+    fn pokemon(egg_move: Option<MoveId>) -> Pokemon {
+        Pokemon {
+            species: GIBLE,
+            form: 0,
+            level: None,
+            ec: 0,
+            pid: 0,
+            shiny: ShinyType::None,
+            ivs: [31; 6],
+            ability: 0,
+            gender: 0,
+            nature: Nature::Jolly,
+            height: 128,
+            weight: 128,
+            item: ItemId(0),
+            egg_move,
+        }
+    }
+
+    #[test]
+    fn invalid_egg_move_lists_the_valid_moves() {
+        let result = Filter::default()
+            .species(vec![GIBLE])
+            .egg_move(vec![DRAGON_DANCE])
+            .validate();
+
+        match result {
+            Err(GeneratorError::InvalidEggMove {
+                egg_move,
+                species,
+                valid_moves,
+            }) => {
+                assert_eq!(egg_move, DRAGON_DANCE);
+                assert_eq!(species, [GIBLE]);
+                assert_eq!(valid_moves, get_available_egg_moves(GIBLE, 0).unwrap());
+                assert!(valid_moves.contains(&OUTRAGE));
+            }
+            result => panic!("expected InvalidEggMove, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn egg_moves_are_checked_against_every_species() {
+        let filter = Filter::default()
+            .species(vec![GIBLE, BAGON])
+            .egg_move(vec![DRAGON_DANCE, OUTRAGE]);
+        assert!(filter.validate().is_ok());
+
+        let result = Filter::default()
+            .species(vec![GIBLE, BAGON])
+            .egg_move(vec![MoveId(33)])
+            .validate();
+        match result {
+            Err(GeneratorError::InvalidEggMove { valid_moves, .. }) => {
+                let ids = valid_moves.iter().map(|m| m.0).collect::<Vec<u16>>();
+                assert_eq!(
+                    ids,
+                    [34, 37, 38, 56, 111, 184, 200, 232, 239, 341, 349, 407, 424, 442]
+                );
+            }
+            result => panic!("expected InvalidEggMove, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn egg_moves_are_not_checked_without_species() {
+        assert!(Filter::default()
+            .egg_move(vec![DRAGON_DANCE])
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn has_egg_move_matches_presence() {
+        let with_move = pokemon(Some(OUTRAGE));
+        let without_move = pokemon(None);
+
+        let filter = Filter::default().has_egg_move(true);
+        assert!(filter.check_pokemon(&with_move));
+        assert!(!filter.check_pokemon(&without_move));
+
+        let filter = Filter::default().has_egg_move(false);
+        assert!(!filter.check_pokemon(&with_move));
+        assert!(filter.check_pokemon(&without_move));
+    }
 }
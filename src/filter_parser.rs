@@ -0,0 +1,678 @@
+use crate::{Filter, FilterNode, GeneratorError, Nature, ShinyType};
+use std::str::FromStr;
+
+/// Characters that end an unquoted word.
+const RESERVED: &[char] = &['(', ')', '[', ']', ',', '&', '|', '!', '<', '>', '=', '"'];
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Token {
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Compare(CompareOp),
+    Word(String),
+}
+
+/// A token and the 1-based column it starts at.
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+/// A value in an expression and the column it starts at. Consecutive unquoted words are joined
+/// with single spaces so names like `Dragon Dance` don't need quotes.
+struct Value {
+    text: String,
+    column: usize,
+}
+
+fn error(column: usize, message: impl Into<String>) -> GeneratorError {
+    GeneratorError::FilterParse {
+        column,
+        message: message.into(),
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Spanned>, GeneratorError> {
+    let chars = expression.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('=', _) => (Token::Compare(CompareOp::Eq), 1),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            (',', _) => (Token::Comma, 1),
+            ('&', _) | ('|', _) => {
+                return Err(error(column, format!("Expected \"{}{}\"", c, c)));
+            }
+            ('"', _) => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or_else(|| error(column, "Unterminated quoted name"))?;
+                let word = chars[i + 1..i + 1 + len].iter().collect::<String>();
+                (Token::Word(word), len + 2)
+            }
+            _ => {
+                let len = chars[i..]
+                    .iter()
+                    .position(|c| c.is_whitespace() || RESERVED.contains(c))
+                    .unwrap_or(chars.len() - i);
+                (Token::Word(chars[i..i + len].iter().collect()), len)
+            }
+        };
+        tokens.push(Spanned { token, column });
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+    end_column: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|t| &t.token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.end_column, |t| t.column)
+    }
+
+    fn advance(&mut self) -> Option<&Spanned> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), GeneratorError> {
+        if self.peek() == Some(&expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(error(self.column(), format!("Expected {}", description)))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<FilterNode, GeneratorError> {
+        let mut nodes = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            nodes.push(self.parse_and()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            FilterNode::Any(nodes)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<FilterNode, GeneratorError> {
+        let mut nodes = vec![self.parse_unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            nodes.push(self.parse_unary()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            FilterNode::All(nodes)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterNode, GeneratorError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(!self.parse_unary()?)
+            }
+            Some(Token::LParen) => {
+                self.position += 1;
+                let node = self.parse_or()?;
+                self.expect(Token::RParen, "\")\"")?;
+                Ok(node)
+            }
+            Some(Token::Word(_)) => self.parse_predicate(),
+            _ => Err(error(
+                self.column(),
+                "Expected a field name, \"!\" or \"(\"",
+            )),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, GeneratorError> {
+        let column = self.column();
+        let mut words = Vec::new();
+        while let Some(Token::Word(word)) = self.peek() {
+            words.push(word.clone());
+            self.position += 1;
+        }
+
+        if words.is_empty() {
+            Err(error(column, "Expected a value"))
+        } else {
+            Ok(Value {
+                text: words.join(" "),
+                column,
+            })
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Value>, GeneratorError> {
+        self.expect(Token::LBracket, "\"[\"")?;
+        let mut values = vec![self.parse_value()?];
+        while self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            values.push(self.parse_value()?);
+        }
+        self.expect(Token::RBracket, "\",\" or \"]\"")?;
+        Ok(values)
+    }
+
+    fn parse_predicate(&mut self) -> Result<FilterNode, GeneratorError> {
+        let (field, column) = match self.advance() {
+            Some(Spanned {
+                token: Token::Word(word),
+                column,
+            }) => (word.to_lowercase().replace('-', "_"), *column),
+            _ => unreachable!("parse_predicate is only called on a word"),
+        };
+
+        let field = Field::from_name(&field).ok_or_else(|| {
+            error(
+                column,
+                format!(
+                    "Unknown field \"{}\", expected one of {}",
+                    field,
+                    Field::NAMES
+                ),
+            )
+        })?;
+
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("in") => {
+                self.position += 1;
+                let values = self.parse_list()?;
+                field.matching(&values)
+            }
+            Some(Token::Compare(op)) => {
+                let op = *op;
+                let op_column = self.column();
+                self.position += 1;
+                let value = self.parse_value()?;
+                field.compare(op, op_column, &value)
+            }
+            _ if field == Field::Shiny => Ok(Filter::default().shiny(true).into()),
+            _ => Err(error(
+                self.column(),
+                "Expected a comparison such as \"==\" or \"in\"",
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+enum Field {
+    Shiny,
+    Species,
+    Level,
+    Ivs,
+    PerfectIvs,
+    IvTotal,
+    Stats,
+    Ability,
+    Nature,
+    HiddenPower,
+    Characteristic,
+    Height,
+    Weight,
+    Item,
+    EggMove,
+    Gender,
+}
+
+impl Field {
+    const NAMES: &'static str = "shiny, species, level, ivs, perfect_ivs, iv_total, stats, ability, nature, hidden_power, characteristic, height, weight, item, egg_move, gender";
+
+    fn from_name(name: &str) -> Option<Field> {
+        match name {
+            "shiny" => Some(Field::Shiny),
+            "species" => Some(Field::Species),
+            "level" => Some(Field::Level),
+            "ivs" => Some(Field::Ivs),
+            "perfect_ivs" => Some(Field::PerfectIvs),
+            "iv_total" => Some(Field::IvTotal),
+            "stats" => Some(Field::Stats),
+            "ability" => Some(Field::Ability),
+            "nature" => Some(Field::Nature),
+            "hidden_power" => Some(Field::HiddenPower),
+            "characteristic" => Some(Field::Characteristic),
+            "height" => Some(Field::Height),
+            "weight" => Some(Field::Weight),
+            "item" => Some(Field::Item),
+            "egg_move" => Some(Field::EggMove),
+            "gender" => Some(Field::Gender),
+            _ => None,
+        }
+    }
+
+    /// Node for `field in [values]`, matching Pokemon equal to any of the values.
+    fn matching(self, values: &[Value]) -> Result<FilterNode, GeneratorError> {
+        match self {
+            Field::Nature => {
+                let mut natures = Vec::new();
+                for value in values {
+                    natures.extend(
+                        Nature::parse_group(&value.text).map_err(|e| error(value.column, e))?,
+                    );
+                }
+                Ok(Filter::default().nature(natures).into())
+            }
+            Field::Species => Ok(Filter::default().species(parse_all(values)?).into()),
+            Field::Item => Ok(Filter::default().item(parse_all(values)?).into()),
+            Field::EggMove => Ok(Filter::default().egg_move(parse_all(values)?).into()),
+            Field::Ability => Ok(Filter::default().ability(parse_all(values)?).into()),
+            Field::Gender => Ok(Filter::default()
+                .gender(
+                    values
+                        .iter()
+                        .map(|v| parse_gender(&v.text).map_err(|e| error(v.column, e)))
+                        .collect::<Result<Vec<u8>, GeneratorError>>()?,
+                )
+                .into()),
+            Field::HiddenPower => Ok(Filter::default().hidden_power(parse_all(values)?).into()),
+            Field::Characteristic => {
+                Ok(Filter::default().characteristic(parse_all(values)?).into())
+            }
+            _ => {
+                let mut nodes = values
+                    .iter()
+                    .map(|value| self.compare(CompareOp::Eq, value.column, value))
+                    .collect::<Result<Vec<FilterNode>, GeneratorError>>()?;
+                Ok(if nodes.len() == 1 {
+                    nodes.remove(0)
+                } else {
+                    FilterNode::Any(nodes)
+                })
+            }
+        }
+    }
+
+    fn compare(
+        self,
+        op: CompareOp,
+        op_column: usize,
+        value: &Value,
+    ) -> Result<FilterNode, GeneratorError> {
+        if op == CompareOp::Ne {
+            return Ok(!self.compare(CompareOp::Eq, op_column, value)?);
+        }
+
+        let filter = Filter::default();
+        let filter = match self {
+            Field::Level | Field::Height | Field::Weight => {
+                let bound = parse_number(value, u8::MAX as u32)?;
+                let Some((min, max)) = bounds(op, bound, u8::MAX as u32) else {
+                    return Ok(match_nothing());
+                };
+                let (min, max) = (min as u8, max as u8);
+                match self {
+                    Field::Level => filter.min_level(min).max_level(max),
+                    Field::Height => filter.min_height(min).max_height(max),
+                    _ => filter.min_weight(min).max_weight(max),
+                }
+            }
+            Field::Ivs => {
+                let mut min_ivs = [0; 6];
+                let mut max_ivs = [31; 6];
+                for (i, iv) in parse_stat_list(value, 31)?.into_iter().enumerate() {
+                    if let Some(iv) = iv {
+                        let Some((min, max)) = bounds(op, iv, 31) else {
+                            return Ok(match_nothing());
+                        };
+                        min_ivs[i] = min as u8;
+                        max_ivs[i] = max as u8;
+                    }
+                }
+                filter.min_ivs(min_ivs).max_ivs(max_ivs)
+            }
+            Field::PerfectIvs => {
+                let bound = parse_number(value, 6)?;
+                let Some((min, max)) = bounds(op, bound, 6) else {
+                    return Ok(match_nothing());
+                };
+                filter.min_perfect_ivs(min as u8).max_perfect_ivs(max as u8)
+            }
+            Field::IvTotal => {
+                let bound = parse_number(value, 31 * 6)?;
+                let Some((min, max)) = bounds(op, bound, 31 * 6) else {
+                    return Ok(match_nothing());
+                };
+                filter.min_iv_total(min as u16).max_iv_total(max as u16)
+            }
+            Field::Stats => {
+                let mut min_stats = [0; 6];
+                let mut max_stats = [u16::MAX; 6];
+                for (i, stat) in parse_stat_list(value, u16::MAX as u32)?
+                    .into_iter()
+                    .enumerate()
+                {
+                    if let Some(stat) = stat {
+                        let Some((min, max)) = bounds(op, stat, u16::MAX as u32) else {
+                            return Ok(match_nothing());
+                        };
+                        min_stats[i] = min as u16;
+                        max_stats[i] = max as u16;
+                    }
+                }
+                filter.min_stats(min_stats).max_stats(max_stats)
+            }
+            _ if op != CompareOp::Eq => {
+                return Err(error(
+                    op_column,
+                    "Only \"==\", \"!=\" and \"in\" can be used with this field",
+                ));
+            }
+            Field::EggMove if value.text.eq_ignore_ascii_case("any") => filter.has_egg_move(true),
+            Field::EggMove if value.text.eq_ignore_ascii_case("none") => filter.has_egg_move(false),
+            Field::Shiny => match value.text.to_lowercase().as_str() {
+                "true" | "yes" => filter.shiny(true),
+                "false" | "no" => filter.shiny_type(ShinyType::None),
+                _ => filter.shiny_type(parse(value)?),
+            },
+            Field::Species
+            | Field::Item
+            | Field::EggMove
+            | Field::Ability
+            | Field::Gender
+            | Field::Nature
+            | Field::HiddenPower
+            | Field::Characteristic => {
+                return self.matching(std::slice::from_ref(value));
+            }
+        };
+
+        Ok(filter.into())
+    }
+}
+
+/// A node no Pokemon can match, for comparisons like `level > 255`.
+fn match_nothing() -> FilterNode {
+    FilterNode::Any(Vec::new())
+}
+
+/// Inclusive range of values satisfying `value op bound`, or `None` if nothing up to `max` can.
+fn bounds(op: CompareOp, bound: u32, max: u32) -> Option<(u32, u32)> {
+    let (min, max) = match op {
+        CompareOp::Eq | CompareOp::Ne => (bound, bound),
+        CompareOp::Ge => (bound, max),
+        CompareOp::Gt => (bound.checked_add(1)?, max),
+        CompareOp::Le => (0, bound),
+        CompareOp::Lt => (0, bound.checked_sub(1)?),
+    };
+    (min <= max).then_some((min, max))
+}
+
+fn parse<T: FromStr<Err = String>>(value: &Value) -> Result<T, GeneratorError> {
+    value.text.parse().map_err(|e| error(value.column, e))
+}
+
+fn parse_all<T: FromStr<Err = String>>(values: &[Value]) -> Result<Vec<T>, GeneratorError> {
+    values.iter().map(parse).collect()
+}
+
+fn parse_number(value: &Value, max: u32) -> Result<u32, GeneratorError> {
+    value
+        .text
+        .parse::<u32>()
+        .ok()
+        .filter(|n| *n <= max)
+        .ok_or_else(|| {
+            error(
+                value.column,
+                format!(
+                    "Expected a number from 0 to {}, got \"{}\"",
+                    max, value.text
+                ),
+            )
+        })
+}
+
+/// Parses `x/x/x/x/x/x`, where `x`, `*` or an empty entry leaves that stat unconstrained.
+fn parse_stat_list(value: &Value, max: u32) -> Result<[Option<u32>; 6], GeneratorError> {
+    let parts = value.text.split('/').collect::<Vec<&str>>();
+    if parts.len() != 6 {
+        return Err(error(
+            value.column,
+            format!(
+                "Expected 6 values separated by \"/\", got \"{}\"",
+                value.text
+            ),
+        ));
+    }
+
+    let mut stats = [None; 6];
+    let mut column = value.column;
+    for (stat, part) in stats.iter_mut().zip(parts) {
+        if !matches!(part, "" | "x" | "X" | "*") {
+            *stat = Some(parse_number(
+                &Value {
+                    text: part.to_string(),
+                    column,
+                },
+                max,
+            )?);
+        }
+        column += part.chars().count() + 1;
+    }
+    Ok(stats)
+}
+
+/// Parses a gender as stored in `Pokemon::gender`: `0`/`male`, `1`/`female` or
+/// `2`/`genderless`, ignoring case. `m`, `f`, `♂`, `♀` and `-` work too.
+pub fn parse_gender(s: &str) -> Result<u8, String> {
+    let s = s.trim();
+    match s.to_lowercase().as_str() {
+        "0" | "male" | "m" | "♂" => Ok(0),
+        "1" | "female" | "f" | "♀" => Ok(1),
+        "2" | "genderless" | "-" => Ok(2),
+        _ => Err(format!(
+            "Invalid gender \"{}\", expected male, female or genderless",
+            s
+        )),
+    }
+}
+
+/// Parses a filter expression into a `FilterNode`.
+///
+/// Conditions are combined with `&&`, `||`, `!` and parentheses, with `&&` binding tighter than
+/// `||`. Each condition is one of:
+/// - `shiny`, or `shiny == star`/`square`/`none`
+/// - `field == value`, `field != value` or `field in [value, ...]` for `species`, `item`,
+///   `egg_move`, `nature`, `hidden_power`, `characteristic`, `ability` (slot 0 or 1, or a
+///   name) and `gender`
+/// - `egg_move == any` or `egg_move == none` for Pokemon with or without an egg move
+/// - `field op number` for `level`, `height`, `weight`, `perfect_ivs` (the number of 31 IVs)
+///   and `iv_total`, where `op` is one of `==`, `!=`, `<`, `<=`, `>` or `>=`
+/// - `ivs op x/x/x/x/x/x` or `stats op x/x/x/x/x/x`, where `x` leaves a stat unconstrained
+///
+/// Names are matched against the English text tables, ignoring case. Names with spaces can be
+/// written as is or quoted. Natures also accept `neutral` and stat modifiers like `+Spe-Atk`.
+/// For example:
+/// `shiny && species in [Gible, Bagon] && ivs >= 31/x/31/31/31/31 && nature in [Jolly, Adamant]`
+pub fn parse_filter(expression: &str) -> Result<FilterNode, GeneratorError> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        position: 0,
+        end_column: expression.chars().count() + 1,
+    };
+
+    let node = parser.parse_or()?;
+    if parser.position < parser.tokens.len() {
+        return Err(error(
+            parser.column(),
+            "Expected \"&&\", \"||\" or the end of the filter",
+        ));
+    }
+    Ok(node)
+}
+
+impl FromStr for FilterNode {
+    type Err = GeneratorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_filter(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ability, AbilityId, MoveId, Species};
+
+    fn criteria(node: &FilterNode) -> &Filter {
+        match node {
+            FilterNode::Criteria(filter) => filter,
+            _ => panic!("expected a single filter, got {:?}", node),
+        }
+    }
+
+    fn error_column(expression: &str) -> usize {
+        match parse_filter(expression) {
+            Err(GeneratorError::FilterParse { column, .. }) => column,
+            result => panic!("expected a parse error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parses_the_documented_example() {
+        let node = parse_filter(
+            "shiny && species in [Gible, Bagon] && ivs >= 31/x/31/31/31/31 && nature in [Jolly, Adamant]",
+        )
+        .unwrap();
+        let FilterNode::All(nodes) = &node else {
+            panic!("expected an All node, got {:?}", node);
+        };
+        assert_eq!(nodes.len(), 4);
+
+        assert!(criteria(&nodes[0]).shiny);
+        assert_eq!(
+            criteria(&nodes[1]).species,
+            Some(vec![Species(443), Species(371)])
+        );
+        assert_eq!(criteria(&nodes[2]).min_ivs, [31, 0, 31, 31, 31, 31]);
+        assert_eq!(criteria(&nodes[2]).max_ivs, [31; 6]);
+        assert_eq!(
+            criteria(&nodes[3]).nature,
+            Some(vec![Nature::Jolly, Nature::Adamant])
+        );
+    }
+
+    #[test]
+    fn joins_multi_word_names() {
+        let node = parse_filter("egg_move == Dragon Dance").unwrap();
+        assert_eq!(criteria(&node).egg_move, Some(vec![MoveId(349)]));
+
+        let node = parse_filter("species in [Mr. Mime, \"Mime Jr.\"]").unwrap();
+        assert_eq!(
+            criteria(&node).species,
+            Some(vec![Species(122), Species(439)])
+        );
+    }
+
+    #[test]
+    fn not_equal_negates_and_in_matches_any_value() {
+        let node = parse_filter("nature != Jolly").unwrap();
+        let FilterNode::Not(inner) = &node else {
+            panic!("expected a Not node, got {:?}", node);
+        };
+        assert_eq!(criteria(inner).nature, Some(vec![Nature::Jolly]));
+
+        let node = parse_filter("level in [20, 30]").unwrap();
+        let FilterNode::Any(nodes) = &node else {
+            panic!("expected an Any node, got {:?}", node);
+        };
+        let levels = nodes
+            .iter()
+            .map(|n| (criteria(n).min_level, criteria(n).max_level))
+            .collect::<Vec<_>>();
+        assert_eq!(levels, [(Some(20), Some(20)), (Some(30), Some(30))]);
+    }
+
+    #[test]
+    fn unclosed_paren_reports_the_end_of_input() {
+        let expression = "(shiny && level > 20";
+        assert_eq!(error_column(expression), expression.len() + 1);
+    }
+
+    #[test]
+    fn stat_list_errors_point_at_the_bad_entry() {
+        // "ivs >= " is 7 columns and "31/31/x/" another 8, so "32" starts at column 16.
+        assert_eq!(error_column("ivs >= 31/31/x/32/31/31"), 16);
+        assert_eq!(error_column("ivs >= 31/31/31"), 8);
+    }
+
+    #[test]
+    fn abilities_and_genders_accept_names() {
+        let node = parse_filter("ability in [0, Rough Skin] && gender in [female, -]").unwrap();
+        let FilterNode::All(nodes) = &node else {
+            panic!("expected an All node, got {:?}", node);
+        };
+        assert_eq!(
+            criteria(&nodes[0]).ability,
+            Some(vec![Ability::Slot(0), Ability::Id(AbilityId(24))])
+        );
+        assert_eq!(criteria(&nodes[1]).gender, Some(vec![1, 2]));
+
+        assert_eq!(error_column("ability == 2"), 12);
+        assert_eq!(error_column("gender in [male, 3]"), 18);
+    }
+
+    #[test]
+    fn rejects_species_outside_bdsp() {
+        assert_eq!(error_column("species == 9999"), 12);
+        assert_eq!(error_column("species == 0"), 12);
+        assert_eq!(error_column("species in [Gible, Kleavor]"), 20);
+        assert!(parse_filter("species == 493").is_ok());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{Filter, GeneratorError, Pokemon, PokemonFilter, Species};
+use std::ops::Not;
+
+/// A boolean combination of `Filter` criteria, evaluated against one Pokemon at a time.
+#[derive(Debug)]
+pub enum FilterNode {
+    /// Matches Pokemon that pass the filter. The filter's own `exclusive` flag is ignored.
+    Criteria(Box<Filter>),
+    /// Matches Pokemon that every child matches. An empty list matches everything.
+    All(Vec<FilterNode>),
+    /// Matches Pokemon that at least one child matches. An empty list matches nothing.
+    Any(Vec<FilterNode>),
+    /// Matches Pokemon that the child does not match.
+    Not(Box<FilterNode>),
+}
+
+impl FilterNode {
+    pub fn all(nodes: Vec<FilterNode>) -> FilterNode {
+        FilterNode::All(nodes)
+    }
+
+    pub fn any(nodes: Vec<FilterNode>) -> FilterNode {
+        FilterNode::Any(nodes)
+    }
+
+    /// Validates every `Filter` in the tree. Species filtered by an `All` node's criteria also
+    /// apply to its children, so `species == Gible && egg_move == Outrage` is checked as a whole.
+    pub fn validate(&self) -> Result<(), GeneratorError> {
+        self.validate_with_species(None)
+    }
+
+    fn validate_with_species(&self, species: Option<&[Species]>) -> Result<(), GeneratorError> {
+        match self {
+            FilterNode::Criteria(filter) => {
+                filter.validate()?;
+                match (
+                    intersect(species, filter.species.as_deref()),
+                    &filter.egg_move,
+                ) {
+                    // An empty intersection can't match anything, so there is nothing to report.
+                    (Some(species), Some(egg_move)) if !species.is_empty() => Filter::default()
+                        .species(species)
+                        .egg_move(egg_move.clone())
+                        .validate(),
+                    _ => Ok(()),
+                }
+            }
+            FilterNode::All(nodes) => {
+                let mut species = species.map(|s| s.to_vec());
+                for node in nodes {
+                    if let FilterNode::Criteria(filter) = node {
+                        species = intersect(species.as_deref(), filter.species.as_deref());
+                    }
+                }
+                nodes
+                    .iter()
+                    .try_for_each(|n| n.validate_with_species(species.as_deref()))
+            }
+            FilterNode::Any(nodes) => nodes
+                .iter()
+                .try_for_each(|n| n.validate_with_species(species)),
+            // Species outside of a negation don't narrow what the negated filter asks for.
+            FilterNode::Not(node) => node.validate_with_species(None),
+        }
+    }
+
+    pub fn matches(&self, pokemon: &Pokemon) -> bool {
+        match self {
+            FilterNode::Criteria(filter) => filter.check_pokemon(pokemon),
+            FilterNode::All(nodes) => nodes.iter().all(|n| n.matches(pokemon)),
+            FilterNode::Any(nodes) => nodes.iter().any(|n| n.matches(pokemon)),
+            FilterNode::Not(node) => !node.matches(pokemon),
+        }
+    }
+}
+
+/// A bare node keeps whole advances. Wrap it in a `FilterTree` for exclusive mode.
+impl PokemonFilter for FilterNode {
+    fn validate(&self) -> Result<(), GeneratorError> {
+        FilterNode::validate(self)
+    }
+
+    fn check_pokemon(&self, pokemon: &Pokemon) -> bool {
+        self.matches(pokemon)
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+}
+
+impl Not for FilterNode {
+    type Output = FilterNode;
+
+    fn not(self) -> Self::Output {
+        FilterNode::Not(Box::new(self))
+    }
+}
+
+impl From<Filter> for FilterNode {
+    fn from(filter: Filter) -> Self {
+        FilterNode::Criteria(Box::new(filter))
+    }
+}
+
+/// A `FilterNode` with the advance-level behaviour of a `Filter`: an advance passes if any of its
+/// Pokemon matches the tree, and `exclusive` drops the Pokemon that don't.
+#[derive(Debug)]
+pub struct FilterTree {
+    pub root: FilterNode,
+    pub exclusive: bool,
+}
+
+impl FilterTree {
+    pub fn new(root: FilterNode) -> FilterTree {
+        FilterTree {
+            root,
+            exclusive: false,
+        }
+    }
+
+    pub fn exclusive(mut self, exclusive: bool) -> FilterTree {
+        self.exclusive = exclusive;
+        self
+    }
+}
+
+impl PokemonFilter for FilterTree {
+    fn validate(&self) -> Result<(), GeneratorError> {
+        self.root.validate()
+    }
+
+    fn check_pokemon(&self, pokemon: &Pokemon) -> bool {
+        self.root.matches(pokemon)
+    }
+
+    fn exclusive(&self) -> bool {
+        self.exclusive
+    }
+}
+
+/// Species allowed by both sets, where `None` allows every species.
+fn intersect(a: Option<&[Species]>, b: Option<&[Species]>) -> Option<Vec<Species>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.iter().copied().filter(|s| b.contains(s)).collect()),
+        (Some(s), None) | (None, Some(s)) => Some(s.to_vec()),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MoveId;
+
+    const GIBLE: Species = Species(443);
+    const BAGON: Species = Species(371);
+    const OUTRAGE: MoveId = MoveId(200);
+
+    fn species(species: Vec<Species>) -> FilterNode {
+        Filter::default().species(species).into()
+    }
+
+    fn outrage() -> FilterNode {
+        Filter::default().egg_move(vec![OUTRAGE]).into()
+    }
+
+    #[test]
+    fn all_narrows_species_for_its_children() {
+        let node = FilterNode::all(vec![species(vec![BAGON]), outrage()]);
+        match node.validate() {
+            Err(GeneratorError::InvalidEggMove {
+                egg_move, species, ..
+            }) => {
+                assert_eq!(egg_move, OUTRAGE);
+                assert_eq!(species, [BAGON]);
+            }
+            result => panic!("expected InvalidEggMove, got {:?}", result),
+        }
+
+        let node = FilterNode::all(vec![
+            species(vec![GIBLE, BAGON]),
+            FilterNode::any(vec![outrage()]),
+        ]);
+        assert!(node.validate().is_ok());
+    }
+
+    #[test]
+    fn all_intersects_species_across_leaves() {
+        let node = FilterNode::all(vec![
+            species(vec![GIBLE, BAGON]),
+            species(vec![BAGON]),
+            outrage(),
+        ]);
+        assert!(node.validate().is_err());
+
+        // No species passes both leaves, so the egg move can't be wrong for any of them.
+        let node = FilterNode::all(vec![species(vec![GIBLE]), species(vec![BAGON]), outrage()]);
+        assert!(node.validate().is_ok());
+    }
+
+    #[test]
+    fn negation_is_not_narrowed() {
+        let node = FilterNode::all(vec![species(vec![BAGON]), !outrage()]);
+        assert!(node.validate().is_ok());
+    }
+}
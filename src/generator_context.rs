@@ -0,0 +1,840 @@
+use crate::personal_info::PersonalInfo;
+use crate::statues::StatueConfig;
+use crate::ug_generator::last_advance;
+use crate::xorshift::XorShift;
+use crate::{
+    egg_move_list, enabled_pokemon, level_range, personal_table, rand_mark, special_pokemon,
+    ug_pokemon, Advance, GeneratorError, ItemId, MoveId, Nature, PokeRate, Pokemon, PokemonFilter,
+    PokemonType, RoomType, ShinyRollConfig, ShinyType, SlotKind, Species, StoryFlag, TypeAndSize,
+    TypeRate, UgGenerator, Version, MOVE_TYPE_SWIM,
+};
+use std::thread;
+
+/// The encounter tables carry no form number, so hideaway Pokemon always use their base form.
+/// See `Pokemon::form` for what that means for Shellos and Gastrodon.
+const UG_FORM: u8 = 0;
+
+/// Pokemon that can fill slots of a given type and size value.
+struct SlotRates {
+    value: u16,
+    poke_rates: Vec<PokeRate>,
+    poke_rates_sum: u16,
+    /// `poke_rates` without swimming Pokemon, used once the room's water cap is reached.
+    land_rates: Vec<PokeRate>,
+    land_rates_sum: u16,
+}
+
+/// Everything needed to generate hideaway spawns for one version, story flag,
+/// room, statue set and shiny roll combination, computed up front so advances can
+/// be generated repeatedly without touching the embedded tables.
+pub struct GeneratorContext {
+    special_pokemon_rates: Vec<PokeRate>,
+    special_rates_sum: f32,
+    min_spawn_count: u8,
+    max_spawn_count: u8,
+    levels: bool,
+    min_level: u8,
+    max_level: u8,
+    secret_base_tile_bonus: u32,
+    /// Never empty, since `new` fails with `NoEncounters` instead.
+    type_rates: Vec<TypeRate>,
+    type_rates_sum: u16,
+    /// Sizes each entry of `type_rates` can roll, each with the index of its `slot_rates`
+    /// entry. Every type has at least one size.
+    type_slots: Vec<Vec<(u8, usize)>>,
+    spawn_caps: bool,
+    size_caps: [u8; 4],
+    water_cap: u8,
+    swimming_species: Vec<u16>,
+    slot_rates: Vec<SlotRates>,
+    egg_moves: Vec<(u16, Vec<u16>)>,
+    regular_pid_rolls: u8,
+    rare_pid_rolls: u8,
+}
+
+impl GeneratorContext {
+    pub fn new(
+        version: Version,
+        story_flag: StoryFlag,
+        room: RoomType,
+        statues: &StatueConfig,
+        shiny_rolls: ShinyRollConfig,
+    ) -> Result<Self, GeneratorError> {
+        let mut special_pokemon_rates = special_pokemon(room)
+            .map(|s| PokeRate {
+                monsno: s.monsno,
+                rate: {
+                    if version == Version::BD {
+                        s.d_special_rate
+                    } else {
+                        s.p_special_rate
+                    }
+                },
+            })
+            .collect::<Vec<PokeRate>>();
+        special_pokemon_rates.sort_by_key(|pr| std::cmp::Reverse(pr.rate));
+        let special_rates_sum = special_pokemon_rates
+            .iter()
+            .map(|pr| pr.rate as f32)
+            .sum::<f32>();
+
+        let rand_mark_data = rand_mark(room)?;
+        let (min_level, max_level) = level_range(story_flag)?;
+
+        let enabled_pokemon = enabled_pokemon(version, story_flag, room)?
+            .map(|e| ug_pokemon(e.monsno))
+            .collect::<Result<Vec<_>, GeneratorError>>()?;
+
+        let mut mons_data_indexs = Vec::with_capacity(enabled_pokemon.len() * 2);
+        for pokemon_data in enabled_pokemon.iter() {
+            mons_data_indexs.push(TypeAndSize::new(pokemon_data.type_1, pokemon_data.size));
+            if let Some(type_2) = pokemon_data.type_2 {
+                mons_data_indexs.push(TypeAndSize::new(type_2, pokemon_data.size));
+            }
+        }
+
+        let mut type_rates = PokemonType::ALL
+            .into_iter()
+            .zip(rand_mark_data.typerate.iter())
+            .filter_map(|(r#type, rate)| {
+                if mons_data_indexs.iter().any(|ts| ts.r#type == r#type) {
+                    Some(TypeRate {
+                        r#type,
+                        rate: *rate + statues.get_bonus_rate(r#type),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<TypeRate>>();
+
+        if type_rates.is_empty() {
+            return Err(GeneratorError::NoEncounters { room });
+        }
+
+        type_rates.sort_by_key(|tr| std::cmp::Reverse(tr.rate));
+
+        let type_rates_sum = type_rates.iter().map(|tr| tr.rate).sum::<u16>();
+
+        let type_sizes = type_rates
+            .iter()
+            .map(|tr| {
+                let mut exist_size_list = Vec::with_capacity(4);
+                for ts in mons_data_indexs.iter().filter(|ts| ts.r#type == tr.r#type) {
+                    if !exist_size_list.contains(&ts.size) {
+                        exist_size_list.push(ts.size);
+                    }
+                }
+                (tr.r#type, exist_size_list)
+            })
+            .collect::<Vec<(PokemonType, Vec<u8>)>>();
+
+        let swimming_species = enabled_pokemon
+            .iter()
+            .filter(|p| p.move_type == MOVE_TYPE_SWIM)
+            .map(|p| p.monsno)
+            .collect::<Vec<u16>>();
+
+        let mut slot_rates: Vec<SlotRates> = Vec::new();
+        let mut type_slots = Vec::with_capacity(type_sizes.len());
+        for (r#type, sizes) in type_sizes.iter() {
+            let mut slots = Vec::with_capacity(sizes.len());
+            for size in sizes.iter() {
+                let value = TypeAndSize::new(*r#type, *size).value;
+                if let Some(index) = slot_rates.iter().position(|sr| sr.value == value) {
+                    slots.push((*size, index));
+                    continue;
+                }
+
+                let temp_list = mons_data_indexs
+                    .iter()
+                    .filter(|ts| ts.value == value)
+                    .collect::<Vec<&TypeAndSize>>();
+
+                let mut poke_rates = enabled_pokemon
+                    .iter()
+                    .filter(|pokemon_data| {
+                        temp_list.iter().any(|ts| {
+                            (ts.r#type == pokemon_data.type_1
+                                || Some(ts.r#type) == pokemon_data.type_2)
+                                && pokemon_data.size == ts.size
+                        })
+                    })
+                    .map(|pokemon_data| {
+                        let flag_rate = *pokemon_data.flag_rate.get(story_flag.index()).ok_or(
+                            GeneratorError::MissingFlagRate {
+                                species: Species(pokemon_data.monsno),
+                                story_flag: story_flag as u8,
+                            },
+                        )? as u16;
+                        Ok(PokeRate {
+                            monsno: pokemon_data.monsno,
+                            rate: if !shiny_rolls.diglett {
+                                flag_rate
+                            } else {
+                                flag_rate * pokemon_data.rate_up as u16
+                            },
+                        })
+                    })
+                    .collect::<Result<Vec<PokeRate>, GeneratorError>>()?;
+
+                poke_rates.sort_by_key(|pr| std::cmp::Reverse(pr.rate));
+
+                let poke_rates_sum = poke_rates.iter().map(|pr| pr.rate).sum::<u16>();
+
+                let land_rates = poke_rates
+                    .iter()
+                    .filter(|pr| !swimming_species.contains(&pr.monsno))
+                    .copied()
+                    .collect::<Vec<PokeRate>>();
+                let land_rates_sum = land_rates.iter().map(|pr| pr.rate).sum::<u16>();
+
+                slots.push((*size, slot_rates.len()));
+                slot_rates.push(SlotRates {
+                    value,
+                    poke_rates,
+                    poke_rates_sum,
+                    land_rates,
+                    land_rates_sum,
+                });
+            }
+            type_slots.push(slots);
+        }
+
+        let mut egg_moves: Vec<(u16, Vec<u16>)> = Vec::new();
+        for species in enabled_pokemon
+            .iter()
+            .map(|p| p.monsno)
+            .chain(special_pokemon_rates.iter().map(|pr| pr.monsno))
+        {
+            if !egg_moves.iter().any(|(s, _)| *s == species) {
+                egg_moves.push((species, egg_move_list(species, UG_FORM)));
+            }
+        }
+
+        Ok(Self {
+            special_pokemon_rates,
+            special_rates_sum,
+            min_spawn_count: rand_mark_data.min,
+            max_spawn_count: rand_mark_data.max,
+            levels: false,
+            min_level,
+            max_level,
+            secret_base_tile_bonus: statues.get_spawn_count_bonus(),
+            type_rates,
+            type_rates_sum,
+            type_slots,
+            spawn_caps: false,
+            size_caps: [
+                rand_mark_data.s_max,
+                rand_mark_data.m_max,
+                rand_mark_data.l_max,
+                rand_mark_data.ll_max,
+            ],
+            water_cap: rand_mark_data.water_max,
+            swimming_species,
+            slot_rates,
+            egg_moves,
+            regular_pid_rolls: shiny_rolls.pid_rolls(SlotKind::Regular),
+            rare_pid_rolls: shiny_rolls.pid_rolls(SlotKind::Rare),
+        })
+    }
+
+    /// Fills in `Pokemon::level` from the story flag's range in `UgLevelRange.json`. Off by
+    /// default, leaving levels as `None`.
+    ///
+    /// The ranges are not from the game's data and have not been checked against captures.
+    /// Every room shares them, and the maximum is assumed to be inclusive. The RNG call the
+    /// level is rolled from is spent either way, so the other values don't change.
+    pub fn levels(mut self, levels: bool) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    /// Applies the per-room caps from the `smax`, `mmax`, `lmax`, `llmax` and `watermax` columns
+    /// of `UgRandMark.json` when filling slots. Off by default.
+    ///
+    /// The columns come from the game data, but how the game applies them does not. The rules
+    /// used here are inferred and haven't been checked against the game's code or captures:
+    /// - A size that reached its cap is left out of the size roll, which changes the roll's
+    ///   range. If every size for the rolled type is capped, all of them stay in.
+    /// - Once `watermax` swimming Pokemon have spawned, swimmers are left out of the species
+    ///   roll, unless only swimmers fit the slot.
+    /// - A cap of 0 means no limit, since Spacious Cave has a `watermax` of 0 but can spawn
+    ///   Mantyke.
+    pub fn spawn_caps(mut self, spawn_caps: bool) -> Self {
+        self.spawn_caps = spawn_caps;
+        self
+    }
+
+    /// Generates every spawn for the advance whose state is `rng`, without filtering.
+    pub fn generate_advance(&self, advance: u32, rng: XorShift) -> Advance {
+        let mut spawn_count = self.min_spawn_count;
+        let mut clone = rng;
+
+        let rare_check = clone.rand_range(0, 100);
+        let mut rare_mons_no = 0;
+        if rare_check < 50 {
+            let rare_es_rand: f32 = clone.rand_range_float(0.0, self.special_rates_sum);
+            let index = roll_index(
+                self.special_pokemon_rates.iter().map(|pr| pr.rate),
+                rare_es_rand,
+            );
+            if let Some(special_pokemon_rate) = self.special_pokemon_rates.get(index) {
+                rare_mons_no = special_pokemon_rate.monsno;
+            }
+        }
+
+        let min_max_rand = clone.rand_range(0, 100);
+        if 50u32.saturating_sub(self.secret_base_tile_bonus) <= min_max_rand {
+            spawn_count = self.max_spawn_count;
+        }
+
+        if rare_check < 50 {
+            spawn_count -= 1;
+        }
+
+        let mut poke_slots: Vec<usize> = Vec::with_capacity(spawn_count as usize);
+
+        let mut result = Advance {
+            advance,
+            regular_pokemon: Vec::with_capacity(spawn_count as usize),
+            rare_pokemon: None,
+        };
+
+        let mut size_counts = [0u8; 4];
+        for _ in 0..spawn_count {
+            let type_rand = clone.rand_range_float(0.0, self.type_rates_sum as f32);
+            let exist_size_list =
+                &self.type_slots[roll_index(self.type_rates.iter().map(|tr| tr.rate), type_rand)];
+
+            // Sizes that already hit the room's cap are skipped, unless every size for
+            // this type is capped.
+            let mut open_size_list = exist_size_list
+                .iter()
+                .copied()
+                .filter(|(size, _)| {
+                    let cap = self.size_caps[*size as usize];
+                    !self.spawn_caps || cap == 0 || size_counts[*size as usize] < cap
+                })
+                .collect::<Vec<(u8, usize)>>();
+            if open_size_list.is_empty() {
+                open_size_list = exist_size_list.to_vec();
+            }
+
+            let size_rand = clone.rand_range(0, open_size_list.len() as u32);
+            let (size, slot) = open_size_list[size_rand as usize];
+            size_counts[size as usize] += 1;
+
+            poke_slots.push(slot);
+        }
+
+        let mut water_count = 0;
+        for poke_slot in poke_slots.iter() {
+            let slot_rates = &self.slot_rates[*poke_slot];
+
+            let (poke_rates, poke_rates_sum) = if self.spawn_caps
+                && self.water_cap != 0
+                && water_count >= self.water_cap
+                && !slot_rates.land_rates.is_empty()
+            {
+                (&slot_rates.land_rates, slot_rates.land_rates_sum)
+            } else {
+                (&slot_rates.poke_rates, slot_rates.poke_rates_sum)
+            };
+
+            let slot_rand = clone.rand_range_float(0.0, poke_rates_sum as f32);
+            let species = poke_rates
+                .get(roll_index(poke_rates.iter().map(|pr| pr.rate), slot_rand))
+                .map_or(0, |pr| pr.monsno);
+
+            if self.swimming_species.contains(&species) {
+                water_count += 1;
+            }
+
+            result.regular_pokemon.push(self.generate_pokemon(
+                species,
+                &mut clone,
+                self.regular_pid_rolls,
+            ));
+        }
+
+        if rare_check < 50 {
+            result.rare_pokemon =
+                Some(self.generate_pokemon(rare_mons_no, &mut clone, self.rare_pid_rolls));
+        }
+
+        result
+    }
+
+    fn generate_pokemon(&self, species: u16, clone: &mut XorShift, pid_rolls: u8) -> Pokemon {
+        let personal_info = personal_table::BDSP.get_form_entry(species as usize, UG_FORM as usize);
+
+        let gender_ratio = personal_info.get_gender();
+
+        // The roll takes the single RNG call the generator always spent here.
+        let level = clone.rand_range(self.min_level as u32, self.max_level as u32 + 1) as u8; //level
+
+        let ec = clone.next(); //EC
+        let curr_shiny_rand = clone.next(); //Shiny Rand
+        let mut curr_pid = 0;
+        let mut shiny = ShinyType::None;
+        for _ in 0..pid_rolls {
+            curr_pid = clone.next(); //PID Called again for each extra roll until shiny
+
+            shiny = ShinyType::from_pid(curr_shiny_rand, curr_pid);
+
+            if shiny.is_shiny() {
+                break;
+            }
+        }
+
+        let mut ivs = [0; 6];
+
+        ivs[0] = (clone.next() % 32) as u8; //IV 1
+        ivs[1] = (clone.next() % 32) as u8; //IV 2
+        ivs[2] = (clone.next() % 32) as u8; //IV 3
+        ivs[3] = (clone.next() % 32) as u8; //IV 4
+        ivs[4] = (clone.next() % 32) as u8; //IV 5
+        ivs[5] = (clone.next() % 32) as u8; //IV 6
+        let ability = (clone.next() % 2) as u8;
+        let gender = if gender_ratio != 255 && gender_ratio != 254 && gender_ratio != 0 {
+            let gender_rand = clone.next() % 253;
+            ((gender_rand as usize) + 1 < gender_ratio) as usize
+        } else {
+            gender_ratio % 253
+        };
+
+        let nature = Nature::ALL[(clone.next() % 25) as usize]; //nature
+        let height = (clone.rand_range(0, 0x81) + clone.rand_range(0, 0x80)) as u8; //height
+        let weight = (clone.rand_range(0, 0x81) + clone.rand_range(0, 0x80)) as u8; //weight
+
+        let item_rand = clone.rand_range(0, 100); //item
+        let item = if item_rand < 60 {
+            personal_info.get_item_1()
+        } else if item_rand < 80 {
+            personal_info.get_item_2()
+        } else {
+            personal_info.get_item_3()
+        };
+
+        let mut egg_move_no = None;
+
+        if let Some((_, egg_move_table)) = self.egg_moves.iter().find(|(s, _)| *s == species) {
+            if !egg_move_table.is_empty() {
+                let egg_move_rand = clone.rand_range(0, egg_move_table.len() as u32) as usize;
+                egg_move_no = Some(egg_move_table[egg_move_rand]);
+            }
+        }
+
+        Pokemon {
+            species: Species(species),
+            form: UG_FORM,
+            level: self.levels.then_some(level),
+            ec,
+            pid: curr_pid,
+            shiny,
+            ivs,
+            ability,
+            gender: gender as u8,
+            nature,
+            height,
+            weight,
+            item: ItemId(item as u16),
+            egg_move: egg_move_no.map(MoveId),
+        }
+    }
+
+    /// Generates `initial_advance..=initial_advance + advances` starting from `rng` and keeps
+    /// the advances that pass `filter`. Fails if the last advance does not fit in a `u32`.
+    pub fn run_results<F: PokemonFilter + ?Sized>(
+        &self,
+        initial_advance: u32,
+        advances: u32,
+        rng: XorShift,
+        filter: &F,
+    ) -> Result<Vec<Advance>, GeneratorError> {
+        Ok(UgGenerator::new(self, rng, initial_advance, advances, filter)?.collect())
+    }
+
+    /// Same as [`GeneratorContext::run_results`], but splits the range into one chunk per
+    /// thread. Each chunk jumps its own copy of `rng` to its first advance, and the results
+    /// are merged back in advance order.
+    pub fn run_results_parallel<F: PokemonFilter + Sync + ?Sized>(
+        &self,
+        initial_advance: u32,
+        advances: u32,
+        rng: XorShift,
+        filter: &F,
+        threads: usize,
+    ) -> Result<Vec<Advance>, GeneratorError> {
+        let first = initial_advance as u64;
+        let last = last_advance(initial_advance, advances)? as u64;
+        let total = last - first + 1;
+        let threads = (threads as u64).clamp(1, total);
+        if threads == 1 {
+            return self.run_results(initial_advance, advances, rng, filter);
+        }
+        let chunk_size = total.div_ceil(threads);
+
+        thread::scope(|scope| {
+            let handles = (0..threads)
+                .map(|i| first + i * chunk_size)
+                .take_while(|start| *start <= last)
+                .map(|start| {
+                    let end = (start + chunk_size - 1).min(last);
+                    let mut chunk_rng = rng;
+                    chunk_rng.jump(start as usize);
+                    scope.spawn(move || {
+                        UgGenerator::with_range(self, chunk_rng, start as u32, end as u32, filter)
+                            .collect::<Vec<Advance>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Ok(handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect())
+        })
+    }
+}
+
+/// Index of the rate a roll of `0.0..=sum` of the rates lands on. `rand_range_float` returns
+/// the sum itself when the low 23 bits of the RNG are 0. That roll is past every rate, so it
+/// lands on the last one.
+fn roll_index(rates: impl IntoIterator<Item = u16>, mut rand: f32) -> usize {
+    let mut last = 0;
+    for (i, rate) in rates.into_iter().enumerate() {
+        if rand < rate as f32 {
+            return i;
+        }
+        rand -= rate as f32;
+        last = i;
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Filter;
+
+    fn rng() -> XorShift {
+        XorShift::from_state([0x12345678, 0x9ABCDEF0, 0x0FEDCBA9, 0x87654321])
+    }
+
+    fn context() -> GeneratorContext {
+        GeneratorContext::new(
+            Version::BD,
+            StoryFlag::NationalDex,
+            RoomType::SpaciousCave,
+            &StatueConfig::default(),
+            ShinyRollConfig::default(),
+        )
+        .unwrap()
+    }
+
+    /// Advance numbers with the PIDs of every Pokemon spawned on them.
+    fn pids(results: &[Advance]) -> Vec<(u32, Vec<u32>)> {
+        results
+            .iter()
+            .map(|a| {
+                let pids = a
+                    .regular_pokemon
+                    .iter()
+                    .chain(&a.rare_pokemon)
+                    .map(|p| p.pid)
+                    .collect();
+                (a.advance, pids)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_results_match_sequential_results() {
+        let context = context();
+        let filter = Filter::default().max_ivs([31; 6]);
+        let expected = pids(&context.run_results(0, 5000, rng(), &filter).unwrap());
+        assert_eq!(expected.len(), 5001);
+
+        for threads in [1, 2, 3, 7, 64, 10000] {
+            let results = context
+                .run_results_parallel(0, 5000, rng(), &filter, threads)
+                .unwrap();
+            assert_eq!(pids(&results), expected, "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn parallel_results_match_sequential_results_when_filtered() {
+        let context = context();
+        let filter = Filter::default()
+            .max_ivs([31; 6])
+            .species(vec![Species(81)]);
+        let expected = pids(&context.run_results(0, 20000, rng(), &filter).unwrap());
+
+        for threads in [2, 3, 7] {
+            let results = context
+                .run_results_parallel(0, 20000, rng(), &filter, threads)
+                .unwrap();
+            assert_eq!(pids(&results), expected, "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn parallel_results_match_sequential_results_from_an_initial_advance() {
+        let context = context();
+        let filter = Filter::default().max_ivs([31; 6]);
+        for (initial_advance, advances) in [(123456, 20000), (u32::MAX - 3000, 3000)] {
+            let expected = pids(
+                &context
+                    .run_results(initial_advance, advances, rng(), &filter)
+                    .unwrap(),
+            );
+            assert_eq!(expected.len(), advances as usize + 1);
+            assert_eq!(expected.first().unwrap().0, initial_advance);
+
+            for threads in [1, 2, 3, 7, 64] {
+                let results = context
+                    .run_results_parallel(initial_advance, advances, rng(), &filter, threads)
+                    .unwrap();
+                assert_eq!(pids(&results), expected, "{} threads", threads);
+            }
+        }
+    }
+
+    fn species_and_levels(advance: &Advance) -> Vec<(u16, Option<u8>)> {
+        advance
+            .regular_pokemon
+            .iter()
+            .chain(&advance.rare_pokemon)
+            .map(|p| (p.species.0, p.level))
+            .collect()
+    }
+
+    #[test]
+    fn levels_are_opt_in_and_come_from_the_story_flag_range() {
+        let context = |story_flag| {
+            GeneratorContext::new(
+                Version::BD,
+                story_flag,
+                RoomType::SpaciousCave,
+                &StatueConfig::default(),
+                ShinyRollConfig::default(),
+            )
+            .unwrap()
+        };
+
+        let advance = context(StoryFlag::UndergroundUnlocked).generate_advance(0, rng());
+        assert!(advance.regular_pokemon.iter().all(|p| p.level.is_none()));
+
+        let advance = context(StoryFlag::UndergroundUnlocked)
+            .levels(true)
+            .generate_advance(0, rng());
+        assert_eq!(
+            species_and_levels(&advance),
+            [
+                (401, Some(19)),
+                (198, Some(18)),
+                (66, Some(19)),
+                (41, Some(18)),
+                (401, Some(20)),
+                (417, Some(19)),
+                (406, Some(17)),
+            ]
+        );
+
+        let advance = context(StoryFlag::NationalDex)
+            .levels(true)
+            .generate_advance(0, rng());
+        assert!(advance
+            .regular_pokemon
+            .iter()
+            .all(|p| p.level.is_some_and(|level| (58..=63).contains(&level))));
+    }
+
+    #[test]
+    fn level_and_stat_filters_need_levels() {
+        for filter in [
+            Filter::default().max_ivs([31; 6]).min_level(1),
+            Filter::default().max_ivs([31; 6]).min_stats([1; 6]),
+        ] {
+            let results = context().run_results(0, 100, rng(), &filter).unwrap();
+            assert!(results.is_empty());
+
+            let results = context()
+                .levels(true)
+                .run_results(0, 100, rng(), &filter)
+                .unwrap();
+            assert_eq!(results.len(), 101);
+        }
+    }
+
+    fn regular_species(context: &GeneratorContext, advance: u32) -> Vec<u16> {
+        let mut rng = rng();
+        rng.jump(advance as usize);
+        context
+            .generate_advance(advance, rng)
+            .regular_pokemon
+            .iter()
+            .map(|p| p.species.0)
+            .collect()
+    }
+
+    #[test]
+    fn size_caps_only_apply_when_enabled() {
+        let context = GeneratorContext::new(
+            Version::BD,
+            StoryFlag::NationalDex,
+            RoomType::GrasslandCave,
+            &StatueConfig::default(),
+            ShinyRollConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(regular_species(&context, 5), [168, 435, 329, 213, 70, 166]);
+
+        let context = context.spawn_caps(true);
+        assert_eq!(regular_species(&context, 5), [168, 435, 329, 213, 70, 265]);
+    }
+
+    #[test]
+    fn water_cap_keeps_swimmers_out_once_reached() {
+        let context = GeneratorContext::new(
+            Version::BD,
+            StoryFlag::NationalDex,
+            RoomType::StillWaterCavern,
+            &StatueConfig::default(),
+            ShinyRollConfig::default(),
+        )
+        .unwrap();
+        // Seven swimmers (Whiscash, Tentacruel, Carvanha and Barboach) against a cap of 6.
+        assert_eq!(
+            regular_species(&context, 66335),
+            [340, 70, 73, 443, 340, 73, 318, 339, 274, 340]
+        );
+
+        let context = context.spawn_caps(true);
+        assert_eq!(
+            regular_species(&context, 66335),
+            [340, 70, 73, 443, 340, 73, 318, 339, 274, 366]
+        );
+    }
+
+    #[test]
+    fn rolls_at_the_rate_sum_land_on_the_last_rate() {
+        let rates = [5, 3, 2];
+        assert_eq!(roll_index(rates, 0.0), 0);
+        assert_eq!(roll_index(rates, 4.9), 0);
+        assert_eq!(roll_index(rates, 5.0), 1);
+        assert_eq!(roll_index(rates, 9.9), 2);
+        assert_eq!(roll_index(rates, 10.0), 2);
+        assert_eq!(roll_index([0u16; 0], 0.0), 0);
+    }
+
+    #[test]
+    fn every_room_generates_without_panicking() {
+        for room in [
+            RoomType::SpaciousCave,
+            RoomType::GrasslandCave,
+            RoomType::FountainspringCave,
+            RoomType::RockyCave,
+            RoomType::VolcanicCave,
+            RoomType::SwampyCave,
+            RoomType::DazzlingCave,
+            RoomType::WhiteoutCave,
+            RoomType::IcyCave,
+            RoomType::RiverbankCave,
+            RoomType::SandsearCave,
+            RoomType::StillWaterCavern,
+            RoomType::SunlitCavern,
+            RoomType::BigBluffCavern,
+            RoomType::StargleamCavern,
+            RoomType::GlacialCavern,
+            RoomType::BogsunkCavern,
+            RoomType::TyphloCavern,
+        ] {
+            for story_flag in StoryFlag::ALL {
+                for spawn_caps in [false, true] {
+                    let context = GeneratorContext::new(
+                        Version::SP,
+                        story_flag,
+                        room,
+                        &StatueConfig::default(),
+                        ShinyRollConfig::default(),
+                    )
+                    .unwrap()
+                    .spawn_caps(spawn_caps);
+                    let results = context
+                        .run_results(0, 200, rng(), &Filter::default().max_ivs([31; 6]))
+                        .unwrap();
+                    assert_eq!(results.len(), 201);
+                }
+            }
+        }
+    }
+
+    /// Species and PID of the regular slots followed by the rare slot.
+    fn species_and_pids(advance: &Advance) -> Vec<(u16, u32)> {
+        advance
+            .regular_pokemon
+            .iter()
+            .chain(&advance.rare_pokemon)
+            .map(|p| (p.species.0, p.pid))
+            .collect()
+    }
+
+    /// Regression vectors for PID rerolls. These pin the generator's current output and have
+    /// not been checked against in-game captures.
+    #[test]
+    fn diglett_rerolls_regular_slots_and_only_opted_in_rare_slots() {
+        let generate = |shiny_rolls| {
+            let mut rng = rng();
+            rng.jump(5);
+            let context = GeneratorContext::new(
+                Version::BD,
+                StoryFlag::NationalDex,
+                RoomType::GrasslandCave,
+                &StatueConfig::default(),
+                shiny_rolls,
+            )
+            .unwrap();
+            species_and_pids(&context.generate_advance(5, rng))
+        };
+
+        assert_eq!(
+            generate(ShinyRollConfig::default()),
+            [
+                (168, 0x8F781B13),
+                (435, 0xDB95C5F4),
+                (329, 0xE23F56B5),
+                (213, 0x7BB5FE81),
+                (70, 0xFE9CEDF6),
+                (166, 0x871D6462),
+                (123, 0x47164DE7)
+            ]
+        );
+
+        let mut diglett = vec![
+            (168, 0xED1F0C52),
+            (168, 0x23F1A63C),
+            (329, 0x76CE6790),
+            (213, 0xD2458EA3),
+            (332, 0xD9BF3131),
+            (168, 0x3B8754D9),
+            (123, 0x66CCE7D0),
+        ];
+        assert_eq!(generate(ShinyRollConfig::default().diglett(true)), diglett);
+
+        // Rerolling the rare slot only changes the rare PID.
+        diglett[6] = (123, 0x51B6F274);
+        assert_eq!(
+            generate(
+                ShinyRollConfig::default()
+                    .diglett(true)
+                    .slot_kinds(true, true)
+            ),
+            diglett
+        );
+    }
+}
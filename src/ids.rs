@@ -0,0 +1,94 @@
+use crate::resource_util::{get_text, get_text_table, Language, TextTable, TextTables};
+use crate::MAX_SPECIES_ID;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Defines a newtype around a game ID whose name is looked up in one of the text tables.
+macro_rules! text_id {
+    ($(#[$meta:meta])* $name:ident, $table:ident, $kind:literal, $ids:expr) => {
+        $(#[$meta])*
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Default)]
+        pub struct $name(pub u16);
+
+        impl $name {
+            /// English name, or `None` if the ID is past the end of the text table.
+            pub fn name(&self) -> Option<&'static str> {
+                get_text(TextTable::$table, Language::English, self.0 as usize)
+            }
+
+            pub fn localized_name<'a>(&self, text: &'a TextTables) -> Option<&'a str> {
+                text.get(TextTable::$table, self.0 as usize)
+            }
+        }
+
+        impl From<u16> for $name {
+            fn from(id: u16) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                match self.name() {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "{}", self.0),
+                }
+            }
+        }
+
+        /// Parses an ID number or an English name, ignoring case. IDs outside the text table or
+        /// the valid range for the kind are rejected.
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let s = s.trim();
+                let table = get_text_table(TextTable::$table, Language::English);
+                let id = match s.parse::<u16>() {
+                    Ok(id) => Some(id as usize),
+                    Err(_) => table
+                        .iter()
+                        .position(|name| !name.is_empty() && name.trim().eq_ignore_ascii_case(s)),
+                };
+                id.filter(|id| *id < table.len() && ($ids).contains(id))
+                    .map(|id| Self(id as u16))
+                    .ok_or_else(|| format!("Invalid {} \"{}\"", $kind, s))
+            }
+        }
+    };
+}
+
+text_id!(
+    /// National Pokedex number.
+    Species,
+    Species,
+    "species",
+    1..=MAX_SPECIES_ID as usize
+);
+text_id!(
+    /// Move ID, as used for egg moves.
+    MoveId,
+    Moves,
+    "move",
+    0..
+);
+text_id!(
+    /// Held item ID.
+    ItemId,
+    Items,
+    "item",
+    0..
+);
+text_id!(
+    /// Ability ID. Not to be confused with `Pokemon::ability`, which is the ability slot.
+    AbilityId,
+    Abilities,
+    "ability",
+    0..
+);
@@ -1,22 +1,50 @@
+mod characteristic;
+mod error;
 mod filter;
+mod filter_parser;
+mod filter_tree;
 mod flag_util;
+mod generator_context;
+mod ids;
+mod nature;
 pub mod personal_info;
 pub mod personal_info_bdsp;
 pub mod personal_table;
+mod pokemon_type;
 pub mod resource_util;
 mod run_results;
+mod shiny;
+mod stats;
 pub mod statues;
+mod story_flag;
+mod summary;
+mod ug_generator;
 pub mod xorshift;
 
+pub use characteristic::*;
+pub use error::*;
 pub use filter::*;
+pub use filter_parser::*;
+pub use filter_tree::*;
+pub use generator_context::*;
+pub use ids::*;
+use lazy_static::lazy_static;
+pub use nature::*;
+pub use pokemon_type::*;
 pub use run_results::*;
 use serde::Deserialize;
+pub use shiny::*;
+pub use stats::*;
 use std::collections::HashSet;
+pub use story_flag::*;
+pub use summary::*;
+pub use ug_generator::*;
 
 const TAMAGO_WAZA_TABLE: &str = include_str!("../TamagoWazaTable.json");
 const TAMAGO_WAZA_IGNORE_TABLE: &str = include_str!("../UgTamagoWazaIgnoreTable.json");
 const UG_POKEMON_DATA: &str = include_str!("../UgPokemonData.json");
 const UG_RAND_MARK: &str = include_str!("../UgRandMark.json");
+const UG_LEVEL_RANGE: &str = include_str!("../UgLevelRange.json");
 const UG_SPECIAL_POKEMON: &str = include_str!("../UgSpecialPokemon.json");
 const UG_ENCOUNT_02: &str = include_str!("../UgEncount_02.json");
 const UG_ENCOUNT_03: &str = include_str!("../UgEncount_03.json");
@@ -31,6 +59,36 @@ const UG_ENCOUNT_11: &str = include_str!("../UgEncount_11.json");
 const UG_ENCOUNT_12: &str = include_str!("../UgEncount_12.json");
 const UG_ENCOUNT_20: &str = include_str!("../UgEncount_20.json");
 
+lazy_static! {
+    static ref TAMAGO_WAZA: TamagoWazaTable = serde_json::from_str(TAMAGO_WAZA_TABLE).unwrap();
+    static ref TAMAGO_WAZA_IGNORE: TamagoWazaIgnoreTable =
+        serde_json::from_str(TAMAGO_WAZA_IGNORE_TABLE).unwrap();
+    static ref UG_POKEMON_DATA_TABLE: UgPokemonData =
+        serde_json::from_str(UG_POKEMON_DATA).unwrap();
+    static ref UG_RAND_MARK_SHEET: UgRandMarkSheet = serde_json::from_str(UG_RAND_MARK).unwrap();
+    static ref UG_LEVEL_RANGE_SHEET: UgLevelRangeSheet =
+        serde_json::from_str(UG_LEVEL_RANGE).unwrap();
+    static ref UG_SPECIAL_POKEMON_SHEET: UgSpecialPokemon =
+        serde_json::from_str(UG_SPECIAL_POKEMON).unwrap();
+    static ref UG_ENCOUNT_SHEETS: Vec<UgEncountSheet> = [
+        UG_ENCOUNT_02,
+        UG_ENCOUNT_03,
+        UG_ENCOUNT_04,
+        UG_ENCOUNT_05,
+        UG_ENCOUNT_06,
+        UG_ENCOUNT_07,
+        UG_ENCOUNT_08,
+        UG_ENCOUNT_09,
+        UG_ENCOUNT_10,
+        UG_ENCOUNT_11,
+        UG_ENCOUNT_12,
+        UG_ENCOUNT_20,
+    ]
+    .iter()
+    .map(|s| serde_json::from_str(s).unwrap())
+    .collect();
+}
+
 #[derive(Deserialize, Clone)]
 struct UgSpecialPokemon {
     #[serde(rename = "Sheet1")]
@@ -68,13 +126,12 @@ struct UgPokemonData {
 #[derive(Deserialize)]
 struct UgPokemon {
     monsno: u16,
-    #[serde(rename = "type1ID")]
-    type_1_id: i8,
-    #[serde(rename = "type2ID")]
-    type_2_id: i8,
+    #[serde(rename = "type1ID", with = "pokemon_type::game_id")]
+    type_1: PokemonType,
+    #[serde(rename = "type2ID", with = "pokemon_type::game_id::optional")]
+    type_2: Option<PokemonType>,
     size: u8,
     #[serde(rename = "movetype")]
-    #[allow(dead_code)]
     move_type: u8,
     #[serde(rename = "flagrate")]
     flag_rate: Vec<u8>,
@@ -95,9 +152,42 @@ struct UgRandMark {
     file_name: String,
     min: u8,
     max: u8,
+    #[serde(rename = "smax")]
+    s_max: u8,
+    #[serde(rename = "mmax")]
+    m_max: u8,
+    #[serde(rename = "lmax")]
+    l_max: u8,
+    #[serde(rename = "llmax")]
+    ll_max: u8,
+    #[serde(rename = "watermax")]
+    water_max: u8,
     typerate: Vec<u16>,
 }
 
+/// `UgPokemon::move_type` of Pokemon that swim, which count towards `UgRandMark::water_max`.
+const MOVE_TYPE_SWIM: u8 = 3;
+
+#[derive(Deserialize)]
+struct UgLevelRangeSheet {
+    table: Vec<UgLevelRange>,
+}
+
+/// Level range of hideaway Pokemon for one story flag.
+///
+/// Unlike the other tables, `UgLevelRange.json` is not a dump of the game's data. It holds one
+/// range per story flag, so every room uses the same levels, and the ranges have not been
+/// checked against in-game captures. They are only used when `GeneratorContext::levels` is on.
+#[derive(Deserialize)]
+struct UgLevelRange {
+    #[serde(rename = "storyflag")]
+    story_flag: u8,
+    #[serde(rename = "minlv")]
+    min_level: u8,
+    #[serde(rename = "maxlv")]
+    max_level: u8,
+}
+
 #[derive(Copy, Clone, Debug)]
 struct PokeRate {
     monsno: u16,
@@ -106,14 +196,27 @@ struct PokeRate {
 
 #[derive(Copy, Clone, Debug)]
 struct TypeAndSize {
-    r#type: i8,
+    r#type: PokemonType,
     size: u8,
     value: u16,
 }
 
+impl TypeAndSize {
+    fn new(r#type: PokemonType, size: u8) -> Self {
+        Self {
+            r#type,
+            size,
+            value: {
+                let pow = f32::powi(10.0, size as i32);
+                pow as u16 + r#type as u16
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 struct TypeRate {
-    r#type: i8,
+    r#type: PokemonType,
     rate: u16,
 }
 
@@ -126,6 +229,8 @@ struct TamagoWazaTable {
 #[derive(Deserialize)]
 struct TamagoWazaEntry {
     no: u16,
+    #[serde(rename = "formNo")]
+    form_no: u8,
     #[serde(rename = "wazaNo")]
     waza_no: Vec<u16>,
 }
@@ -144,13 +249,13 @@ struct TamagoWazaIgnoreEntry {
     waza: Vec<u16>,
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum Version {
     BD = 2,
     SP,
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum RoomType {
     SpaciousCave = 2,
     GrasslandCave,
@@ -172,55 +277,28 @@ pub enum RoomType {
     TyphloCavern,
 }
 
-pub fn get_available_egg_moves(species: u16) -> Vec<u16> {
-    let egg_move_table = serde_json::from_str::<TamagoWazaTable>(TAMAGO_WAZA_TABLE).unwrap();
-    let egg_move_ignore_table =
-        serde_json::from_str::<TamagoWazaIgnoreTable>(TAMAGO_WAZA_IGNORE_TABLE).unwrap();
-    let hatch_species = personal_table::BDSP
-        .get_form_entry(species as usize, 0)
-        .get_hatch_species();
+pub(crate) const MAX_SPECIES_ID: u16 = 493;
 
-    if let Some(entry) = egg_move_table
-        .data
-        .iter()
-        .find(|e| e.no == hatch_species as u16)
-    {
-        let mut egg_move_table = entry.waza_no.clone();
-        if let Some(ignore_entry) = egg_move_ignore_table
-            .sheet_1
-            .iter()
-            .find(|e| e.monsno == entry.no)
-        {
-            let egg_move_ignore_table = ignore_entry.waza.clone();
-            egg_move_table = egg_move_table
-                .into_iter()
-                .filter(|i| !egg_move_ignore_table.contains(i) || *i == 0)
-                .collect::<Vec<u16>>(); // i == 0 check just in case
-        }
-        egg_move_table.sort();
-        egg_move_table
-    } else {
-        vec![]
+pub fn get_available_egg_moves(species: Species, form: u8) -> Result<Vec<MoveId>, GeneratorError> {
+    if species.0 == 0 || species.0 > MAX_SPECIES_ID {
+        return Err(GeneratorError::InvalidSpecies(species));
     }
+    let mut egg_moves = egg_move_list(species.0, form)
+        .into_iter()
+        .map(MoveId)
+        .collect::<Vec<MoveId>>();
+    egg_moves.sort();
+    Ok(egg_moves)
 }
 
-pub fn available_pokemon(version: Version, story_flag: u8, room: RoomType) -> Vec<u16> {
+pub fn available_pokemon(
+    version: Version,
+    story_flag: StoryFlag,
+    room: RoomType,
+) -> Result<Vec<Species>, GeneratorError> {
     let mut available = HashSet::new();
 
-    let opposite_version = match version {
-        Version::BD => Version::SP,
-        Version::SP => Version::BD,
-    };
-
-    let special_pokemon = serde_json::from_str::<UgSpecialPokemon>(UG_SPECIAL_POKEMON).unwrap();
-
-    let special_pokemon = special_pokemon
-        .sheet_sheet_1
-        .into_iter()
-        .filter(|s| s.id == room as u8)
-        .collect::<Vec<Sheet1>>();
-
-    for pokemon in special_pokemon {
+    for pokemon in special_pokemon(room) {
         match version {
             Version::BD => {
                 if pokemon.d_special_rate > 0 {
@@ -235,40 +313,111 @@ pub fn available_pokemon(version: Version, story_flag: u8, room: RoomType) -> Ve
         }
     }
 
-    let ug_rand_mark = serde_json::from_str::<UgRandMarkSheet>(UG_RAND_MARK).unwrap();
+    for pokemon in enabled_pokemon(version, story_flag, room)? {
+        available.insert(pokemon.monsno);
+    }
+
+    let mut available = available.into_iter().map(Species).collect::<Vec<Species>>();
+
+    available.sort();
+
+    Ok(available)
+}
+
+fn special_pokemon(room: RoomType) -> impl Iterator<Item = &'static Sheet1> {
+    UG_SPECIAL_POKEMON_SHEET
+        .sheet_sheet_1
+        .iter()
+        .filter(move |s| s.id == room as u8)
+}
 
-    let ug_encount_str = match ug_rand_mark
+fn rand_mark(room: RoomType) -> Result<&'static UgRandMark, GeneratorError> {
+    UG_RAND_MARK_SHEET
         .table
         .iter()
         .find(|t| t.id == room as u8)
-        .unwrap()
-        .file_name
-        .trim_start_matches("UgEncount_")
-    {
-        "02" => UG_ENCOUNT_02,
-        "03" => UG_ENCOUNT_03,
-        "04" => UG_ENCOUNT_04,
-        "05" => UG_ENCOUNT_05,
-        "06" => UG_ENCOUNT_06,
-        "07" => UG_ENCOUNT_07,
-        "08" => UG_ENCOUNT_08,
-        "09" => UG_ENCOUNT_09,
-        "10" => UG_ENCOUNT_10,
-        "11" => UG_ENCOUNT_11,
-        "12" => UG_ENCOUNT_12,
-        _ => UG_ENCOUNT_20,
+        .ok_or(GeneratorError::MissingRoom {
+            room,
+            table: "UgRandMark",
+        })
+}
+
+fn level_range(story_flag: StoryFlag) -> Result<(u8, u8), GeneratorError> {
+    UG_LEVEL_RANGE_SHEET
+        .table
+        .iter()
+        .find(|t| t.story_flag == story_flag as u8)
+        .map(|t| (t.min_level, t.max_level))
+        .ok_or(GeneratorError::MissingStoryFlag {
+            story_flag: story_flag as u8,
+            table: "UgLevelRange",
+        })
+}
+
+fn enabled_pokemon(
+    version: Version,
+    story_flag: StoryFlag,
+    room: RoomType,
+) -> Result<impl Iterator<Item = &'static UgEncount>, GeneratorError> {
+    let opposite_version = match version {
+        Version::BD => Version::SP,
+        Version::SP => Version::BD,
     };
 
-    let ug_encount = serde_json::from_str::<UgEncountSheet>(ug_encount_str).unwrap();
-    for pokemon in ug_encount.table {
-        if pokemon.version != opposite_version as u8 && pokemon.zukan_flag <= story_flag {
-            available.insert(pokemon.monsno);
-        }
-    }
+    let ug_encount = match rand_mark(room)?.file_name.trim_start_matches("UgEncount_") {
+        "02" => &UG_ENCOUNT_SHEETS[0],
+        "03" => &UG_ENCOUNT_SHEETS[1],
+        "04" => &UG_ENCOUNT_SHEETS[2],
+        "05" => &UG_ENCOUNT_SHEETS[3],
+        "06" => &UG_ENCOUNT_SHEETS[4],
+        "07" => &UG_ENCOUNT_SHEETS[5],
+        "08" => &UG_ENCOUNT_SHEETS[6],
+        "09" => &UG_ENCOUNT_SHEETS[7],
+        "10" => &UG_ENCOUNT_SHEETS[8],
+        "11" => &UG_ENCOUNT_SHEETS[9],
+        "12" => &UG_ENCOUNT_SHEETS[10],
+        _ => &UG_ENCOUNT_SHEETS[11],
+    };
+
+    Ok(ug_encount
+        .table
+        .iter()
+        .filter(move |e| e.version != opposite_version as u8 && e.zukan_flag <= story_flag as u8))
+}
 
-    let mut available = available.into_iter().collect::<Vec<u16>>();
+fn ug_pokemon(species: u16) -> Result<&'static UgPokemon, GeneratorError> {
+    UG_POKEMON_DATA_TABLE
+        .table
+        .iter()
+        .find(|p| p.monsno == species)
+        .ok_or(GeneratorError::MissingSpecies {
+            species: Species(species),
+            table: "UgPokemonData",
+        })
+}
 
-    available.sort();
+/// Egg moves in table order, which is the order the generator indexes into.
+fn egg_move_list(species: u16, form: u8) -> Vec<u16> {
+    let personal_info = personal_table::BDSP.get_form_entry(species as usize, form as usize);
+    let hatch_species = personal_info.get_hatch_species();
+    let hatch_form = personal_info.get_hatch_form_index();
 
-    available
+    if let Some(entry) = TAMAGO_WAZA
+        .data
+        .iter()
+        .find(|e| e.no == hatch_species as u16 && e.form_no == hatch_form as u8)
+    {
+        let mut egg_move_table = entry.waza_no.clone();
+        if let Some(ignore_entry) = TAMAGO_WAZA_IGNORE
+            .sheet_1
+            .iter()
+            .find(|e| e.monsno == entry.no)
+        {
+            // i == 0 check just in case
+            egg_move_table.retain(|i| !ignore_entry.waza.contains(i) || *i == 0);
+        }
+        egg_move_table
+    } else {
+        vec![]
+    }
 }
@@ -1,16 +1,19 @@
 #![allow(unused)]
 
-use bdsp_ug_generator::resource_util::load_string_list;
+use bdsp_ug_generator::resource_util::{TextTable, TextTables};
 use bdsp_ug_generator::statues::{get_statue_data, StatueConfig};
 use bdsp_ug_generator::xorshift::XorShift;
 use bdsp_ug_generator::{
-    available_pokemon, personal_table, run_results, Filter, Pokemon, RoomType, Version,
+    available_pokemon, parse_filter, parse_gender, personal_table, run_results, Ability, Advance,
+    Characteristic, Filter, FilterNode, FilterTree, GeneratorContext, ItemId, MoveId, Nature,
+    Pokemon, PokemonFilter, PokemonType, RoomType, ShinyRollConfig, ShinyType, Species, Stat,
+    StoryFlag, SummaryFormat, UgGenerator, Version,
 };
 use clap::{ArgEnum, Parser};
-use lazy_static::lazy_static;
-use std::fmt::Write as fmt_write;
+use std::fmt::Display;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
+use std::str::FromStr;
 
 #[derive(Parser)]
 struct Cli {
@@ -20,10 +23,33 @@ struct Cli {
     room: ArgRoomType,
     #[clap(short, long)]
     diglett: bool,
-    #[clap(short = 'f', long, default_value = "6")]
-    story_flag: u8,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "PID rerolls from sources other than Diglett"
+    )]
+    extra_pid_rolls: u8,
+    #[clap(
+        long,
+        help = "Also apply Diglett and extra PID rolls to the rare slot. Not yet checked against captures"
+    )]
+    rare_rolls: bool,
+    #[clap(
+        short = 'f',
+        long,
+        default_value = "6",
+        help = "Story progress from 1 to 6: underground-unlocked, strength, defog, seven-badges, waterfall, national-dex"
+    )]
+    story_flag: StoryFlag,
+    #[clap(
+        long,
+        help = "Apply the per-room size and water spawn caps. How the game applies them is unverified"
+    )]
+    spawn_caps: bool,
     #[clap(short = 's', long)]
     shiny_only: bool,
+    #[clap(long, help = "Options are none, star or square")]
+    shiny_type: Option<ShinyType>,
     #[clap(
         long,
         default_value = "0/0/0/0/0/0",
@@ -36,23 +62,130 @@ struct Cli {
         help = "Input format is x/x/x/x/x/x. Values can be elided for the default of 31. Ex /0////"
     )]
     max_ivs: String,
-    #[clap(long, help = "Input pokemon species number")]
-    species: Option<u16>,
-    #[clap(long, help = "Input is a comma separated list of nature IDs")]
+    #[clap(long, help = "Minimum number of IVs that are 31")]
+    min_perfect_ivs: Option<u8>,
+    #[clap(long, help = "Maximum number of IVs that are 31")]
+    max_perfect_ivs: Option<u8>,
+    #[clap(long, help = "Minimum sum of all six IVs")]
+    min_iv_total: Option<u16>,
+    #[clap(long, help = "Maximum sum of all six IVs")]
+    max_iv_total: Option<u16>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of stats whose IV must be 0. Ex atk,spe"
+    )]
+    zero_ivs: Option<String>,
+    #[clap(
+        long,
+        requires = "levels",
+        help = "Minimum stats at the Pokemon's unverified level. Input format is x/x/x/x/x/x. Values can be elided"
+    )]
+    min_stats: Option<String>,
+    #[clap(
+        long,
+        requires = "levels",
+        help = "Maximum stats at the Pokemon's unverified level. Input format is x/x/x/x/x/x. Values can be elided"
+    )]
+    max_stats: Option<String>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of species names or national dex numbers"
+    )]
+    species: Option<String>,
+    #[clap(
+        long,
+        help = "Roll and show levels. The level ranges are unverified and the same for every room"
+    )]
+    levels: bool,
+    #[clap(
+        long,
+        requires = "levels",
+        help = "Minimum level to match. Levels are unverified"
+    )]
+    min_level: Option<u8>,
+    #[clap(
+        long,
+        requires = "levels",
+        help = "Maximum level to match. Levels are unverified"
+    )]
+    max_level: Option<u8>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of nature names or IDs, stat modifiers or neutral. Ex --nature=adamant,+spe-atk,neutral"
+    )]
     nature: Option<String>,
-    #[clap(long, help = "Input is 0 or 1 for ability 1 and 2")]
-    ability: Option<u8>,
-    #[clap(long, help = "Input is a item ID number")]
-    item: Option<u16>,
-    #[clap(long, help = "Input is a move ID number")]
-    egg_move: Option<u16>,
-    #[clap(long, help = "Options are 0, 1, 2 for male, female, genderless")]
-    gender: Option<u8>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of Hidden Power types, by name or type ID. Ex fire,ice"
+    )]
+    hidden_power: Option<String>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of characteristic IDs (0-29) or names. Ex likes-to-thrash-about"
+    )]
+    characteristic: Option<String>,
+    #[clap(long, help = "Minimum height scalar (0-255) to match")]
+    min_height: Option<u8>,
+    #[clap(long, help = "Maximum height scalar (0-255) to match")]
+    max_height: Option<u8>,
+    #[clap(long, help = "Minimum weight scalar (0-255) to match")]
+    min_weight: Option<u8>,
+    #[clap(long, help = "Maximum weight scalar (0-255) to match")]
+    max_weight: Option<u8>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of 0 or 1 for ability 1 and 2, or ability names"
+    )]
+    ability: Option<String>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of item names or ID numbers"
+    )]
+    item: Option<String>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of move names or ID numbers"
+    )]
+    egg_move: Option<String>,
+    #[clap(
+        long,
+        help = "Input is a comma separated list of male, female, genderless or 0, 1, 2"
+    )]
+    gender: Option<String>,
+    #[clap(
+        long,
+        help = "Filter expression, combined with the other filter options. Ex \"shiny && species in [Gible, Bagon] && ivs >= 31/x/31/31/31/31\""
+    )]
+    filter: Option<String>,
     #[clap(
         short,
         help = "Exclude pokemon within frames that do not match filter settings"
     )]
     exclusive: bool,
+    #[clap(
+        long,
+        help = "Print each Pokemon on a single line prefixed by its advance"
+    )]
+    one_line: bool,
+    #[clap(
+        long,
+        help = "Directory of species.txt, forms.txt, moves.txt, items.txt, abilities.txt, natures.txt, types.txt and characteristics.txt to print names from, one name per line in the order of the English tables. Missing files fall back to English"
+    )]
+    text_dir: Option<String>,
+    #[clap(
+        short,
+        long,
+        default_value = "1",
+        help = "Number of threads to search with. Results are printed once the search finishes when above 1"
+    )]
+    threads: usize,
+    #[clap(
+        short,
+        long,
+        default_value = "0",
+        help = "Advance to start searching from. Reported advances include this offset"
+    )]
+    initial_advances: u32,
     advances: u32,
     s0: String,
     s1: String,
@@ -122,144 +255,246 @@ impl From<ArgRoomType> for RoomType {
     }
 }
 
-fn write_pokemon(pokemon: &Pokemon, string: &mut String) {
-    let personal_info = personal_table::BDSP.get_form_entry(pokemon.species as usize, 0);
-    let ability = match pokemon.ability {
-        0 => personal_info.get_ability_1(),
-        _ => personal_info.get_ability_2(),
-    };
-    writeln!(string, "Species: {}\nPID: {:08X} EC: {:08X} Shiny: {}\nIVs: {:?} Ability: {} Gender: {}\nNature: {} Item: {}{}\n", SPECIES_EN[pokemon.species as usize], pokemon.pid, pokemon.ec, pokemon.shiny, pokemon.ivs, ABILITIES_EN[ability],  GENDER_SYMBOLS[pokemon.gender as usize], NATURES_EN[pokemon.nature as usize].trim(),
-             ITEMS_EN[pokemon.item as usize].trim(),
-             if let Some(no) = pokemon.egg_move {
-                 format!(" Egg Move: {}", MOVES_EN[no as usize].trim())
-             } else {
-                 "".to_string()
-             }).unwrap();
+/// Loads the `<table>.txt` files in `dir` over the English text. Missing files are skipped.
+fn load_text_dir(dir: &str) -> Result<TextTables, String> {
+    let mut text = TextTables::default();
+    for table in TextTable::ALL {
+        let path = std::path::Path::new(dir).join(format!("{}.txt", table.name()));
+        let Ok(mut file) = File::open(&path) else {
+            continue;
+        };
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        text.load(table, &contents).map_err(|e| e.to_string())?;
+    }
+    Ok(text)
+}
+
+/// Parses a comma separated list, skipping empty entries.
+fn parse_list<T: FromStr>(name: &str, value: Option<String>) -> Result<Option<Vec<T>>, String>
+where
+    T::Err: Display,
+{
+    parse_list_with(name, value, str::parse)
+}
+
+/// Parses a comma separated list with `parse`, skipping empty entries.
+fn parse_list_with<T, E: Display>(
+    name: &str,
+    value: Option<String>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Option<Vec<T>>, String> {
+    value
+        .map(|s| {
+            s.split(',')
+                .map(str::trim)
+                .filter(|i| !i.is_empty())
+                .map(|i| parse(i).map_err(|e| format!("Failed to parse {} \"{}\": {}", name, i, e)))
+                .collect()
+        })
+        .transpose()
+}
+
+fn write_advance(
+    advance: &Advance,
+    format: SummaryFormat,
+    text: &TextTables,
+    out: &mut impl Write,
+) {
+    match format {
+        SummaryFormat::OneLine => writeln!(out, "{}", advance.summary(format, text)),
+        SummaryFormat::MultiLine => writeln!(out, "{}\n", advance.summary(format, text)),
+    }
+    .unwrap();
+    out.flush().unwrap();
+}
+
+fn parse_seed(name: &str, value: &str) -> Result<u32, String> {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Failed to parse {} \"{}\" to u32: {}", name, value, e))
+}
+
+fn parse_stat_list<T>(name: &str, value: &str, default: T) -> Result<[T; 6], String>
+where
+    T: Copy + FromStr,
+    T::Err: Display,
+{
+    let mut values = [default; 6];
+
+    for (i, val) in value.split('/').take(6).enumerate() {
+        if !val.is_empty() {
+            values[i] = val
+                .parse::<T>()
+                .map_err(|e| format!("Failed to parse {} {} \"{}\": {}", name, i, val, e))?;
+        }
+    }
+
+    Ok(values)
 }
 
 fn main() {
     let cli: Cli = Cli::parse();
 
+    if let Err(e) = run(cli) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
     let mut statue_config;
 
     let file_open = File::open("statue_config.json");
     if let Ok(mut file) = file_open {
         let mut string = String::new();
-        file.read_to_string(&mut string).unwrap();
-        statue_config = serde_json::from_str::<StatueConfig>(&string).unwrap();
+        file.read_to_string(&mut string)
+            .map_err(|e| format!("Failed to read statue_config.json: {}", e))?;
+        statue_config = StatueConfig::from_json(&string).map_err(|e| e.to_string())?;
     } else {
         statue_config = StatueConfig::default();
     }
 
+    let text = match &cli.text_dir {
+        Some(dir) => load_text_dir(dir)?,
+        None => TextTables::default(),
+    };
+
+    let s0 = parse_seed("s0", &cli.s0)?;
+    let s1 = parse_seed("s1", &cli.s1)?;
+    let s2 = parse_seed("s2", &cli.s2)?;
+    let s3 = parse_seed("s3", &cli.s3)?;
+    println!("Initial Advances: {}", cli.initial_advances);
     println!("Advances: {}", cli.advances);
-    let s0 = cli.s0.trim_start_matches("0x");
-    let s0 = u32::from_str_radix(s0, 16).expect("Failed to parse s0 to u32");
-    let s1 = cli.s1.trim_start_matches("0x");
-    let s1 = u32::from_str_radix(s1, 16).expect("Failed to parse s1 to u32");
-    let s2 = cli.s2.trim_start_matches("0x");
-    let s2 = u32::from_str_radix(s2, 16).expect("Failed to parse s2 to u32");
-    let s3 = cli.s3.trim_start_matches("0x");
-    let s3 = u32::from_str_radix(s3, 16).expect("Failed to parse s3 to u32");
     println!("s0: {:#08X}", s0);
     println!("s1: {:#08X}", s1);
     println!("s2: {:#08X}", s2);
     println!("s3: {:#08X}", s3);
     println!();
 
-    let min_split = cli.min_ivs.split('/');
-    let max_split = cli.max_ivs.split('/');
+    let min_ivs = parse_stat_list("min iv", &cli.min_ivs, 0)?;
+    let max_ivs = parse_stat_list("max iv", &cli.max_ivs, 31)?;
+    let min_stats = cli
+        .min_stats
+        .map(|s| parse_stat_list("min stat", &s, 0))
+        .transpose()?;
+    let max_stats = cli
+        .max_stats
+        .map(|s| parse_stat_list("max stat", &s, u16::MAX))
+        .transpose()?;
 
-    let mut min_ivs = [0, 0, 0, 0, 0, 0];
+    let nature = cli
+        .nature
+        .map(|s| {
+            s.split(',')
+                .filter(|i| !i.trim().is_empty())
+                .map(Nature::parse_group)
+                .collect::<Result<Vec<Vec<Nature>>, String>>()
+                .map(|groups| groups.concat())
+        })
+        .transpose()?;
 
-    for (i, val) in min_split.take(6).enumerate() {
-        if !val.is_empty() {
-            min_ivs[i] = val
-                .parse::<u8>()
-                .unwrap_or_else(|_| panic!("Failed to parse min iv {}", i));
-        }
-    }
-
-    let mut max_ivs = [31, 31, 31, 31, 31, 31];
-
-    for (i, val) in max_split.take(6).enumerate() {
-        if !val.is_empty() {
-            max_ivs[i] = val
-                .parse::<u8>()
-                .unwrap_or_else(|_| panic!("Failed to parse max iv {}", i));
-        }
-    }
-
-    let nature = cli.nature.map(|s| {
-        s.split(',')
-            .filter_map(|i| {
-                if i.is_empty() {
-                    None
-                } else {
-                    Some(i.parse().expect("Failed to parse nature to u8"))
-                }
-            })
-            .collect::<Vec<u8>>()
-    });
+    let hidden_power = parse_list::<PokemonType>("hidden power type", cli.hidden_power)?;
+    let characteristic = parse_list::<Characteristic>("characteristic", cli.characteristic)?;
+    let zero_ivs = parse_list::<Stat>("zero iv stat", cli.zero_ivs)?;
+    let species = parse_list::<Species>("species", cli.species)?;
+    let ability = parse_list::<Ability>("ability", cli.ability)?;
+    let item = parse_list::<ItemId>("item", cli.item)?;
+    let (egg_move, has_egg_move) = match cli.egg_move.as_deref().map(str::to_lowercase) {
+        Some(s) if s == "any" => (None, Some(true)),
+        Some(s) if s == "none" => (None, Some(false)),
+        _ => (parse_list::<MoveId>("egg move", cli.egg_move)?, None),
+    };
+    let gender = parse_list_with("gender", cli.gender, parse_gender)?;
 
     let filter = Filter {
         shiny: cli.shiny_only,
-        species: cli.species,
+        shiny_type: cli.shiny_type,
+        species,
+        min_level: cli.min_level,
+        max_level: cli.max_level,
         min_ivs,
         max_ivs,
-        ability: cli.ability,
+        min_perfect_ivs: cli.min_perfect_ivs,
+        max_perfect_ivs: cli.max_perfect_ivs,
+        min_iv_total: cli.min_iv_total,
+        max_iv_total: cli.max_iv_total,
+        zero_ivs,
+        min_stats,
+        max_stats,
+        ability,
         nature,
-        item: cli.item,
-        egg_move: cli.egg_move,
-        gender: cli.gender,
+        hidden_power,
+        characteristic,
+        min_height: cli.min_height,
+        max_height: cli.max_height,
+        min_weight: cli.min_weight,
+        max_weight: cli.max_weight,
+        item,
+        egg_move,
+        has_egg_move,
+        gender,
         exclusive: cli.exclusive,
     };
 
+    let root = match cli.filter {
+        Some(expression) => FilterNode::all(vec![
+            filter.into(),
+            parse_filter(&expression).map_err(|e| e.to_string())?,
+        ]),
+        None => filter.into(),
+    };
+    let filter = FilterTree::new(root).exclusive(cli.exclusive);
+    filter.validate().map_err(|e| e.to_string())?;
+
+    let format = if cli.one_line {
+        SummaryFormat::OneLine
+    } else {
+        SummaryFormat::MultiLine
+    };
+
     let rng = XorShift::from_state([s0, s1, s2, s3]);
 
-    let results = run_results(
-        cli.advances,
-        rng,
+    let context = GeneratorContext::new(
         cli.version.into(),
         cli.story_flag,
         cli.room.into(),
-        filter,
-        cli.diglett,
         &statue_config,
-    );
-
-    let mut print = String::new();
-
-    for result in results.iter() {
-        writeln!(
-            print,
-            "-------------------------------------------\nAdvances: {}",
-            result.advance
-        )
-        .unwrap();
-        for pokemon in result.regular_pokemon.iter() {
-            write_pokemon(pokemon, &mut print);
-        }
+        ShinyRollConfig::default()
+            .diglett(cli.diglett)
+            .extra_rolls(cli.extra_pid_rolls)
+            .slot_kinds(true, cli.rare_rolls),
+    )
+    .map_err(|e| e.to_string())?
+    .levels(cli.levels)
+    .spawn_caps(cli.spawn_caps);
+
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
 
-        if let Some(pokemon) = &result.rare_pokemon {
-            write_pokemon(pokemon, &mut print);
+    if cli.threads > 1 {
+        for result in context
+            .run_results_parallel(
+                cli.initial_advances,
+                cli.advances,
+                rng,
+                &filter,
+                cli.threads,
+            )
+            .map_err(|e| e.to_string())?
+        {
+            write_advance(&result, format, &text, &mut stdout);
+        }
+    } else {
+        let generator =
+            UgGenerator::new(&context, rng, cli.initial_advances, cli.advances, &filter)
+                .map_err(|e| e.to_string())?;
+        for result in generator {
+            write_advance(&result, format, &text, &mut stdout);
         }
     }
 
-    println!("{}", print);
-}
-
-pub const GENDER_SYMBOLS: [char; 3] = ['♂', '♀', '-'];
-
-const SPECIES_EN_RAW: &str = include_str!("../resources/text/other/en/species_en.txt");
-const ABILITIES_EN_RAW: &str = include_str!("../resources/text/other/en/abilities_en.txt");
-const NATURES_EN_RAW: &str = include_str!("../resources/text/other/en/natures_en.txt");
-const MOVES_EN_RAW: &str = include_str!("../resources/text/other/en/moves_en.txt");
-const ITEMS_EN_RAW: &str = include_str!("../resources/text/items/items_en.txt");
+    writeln!(stdout).unwrap();
 
-lazy_static! {
-    pub static ref SPECIES_EN: Vec<&'static str> = load_string_list(SPECIES_EN_RAW);
-    pub static ref ABILITIES_EN: Vec<&'static str> = load_string_list(ABILITIES_EN_RAW);
-    pub static ref NATURES_EN: Vec<&'static str> = load_string_list(NATURES_EN_RAW);
-    pub static ref MOVES_EN: Vec<&'static str> = load_string_list(MOVES_EN_RAW);
-    pub static ref ITEMS_EN: Vec<&'static str> = load_string_list(ITEMS_EN_RAW);
+    Ok(())
 }
@@ -0,0 +1,272 @@
+use crate::resource_util::{get_text, Language, TextTable, TextTables};
+use crate::{GeneratorError, Stat};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The stats natures affect, in the order they cycle through them.
+const NATURE_STATS: [Stat; 5] = [
+    Stat::Attack,
+    Stat::Defense,
+    Stat::Speed,
+    Stat::SpAttack,
+    Stat::SpDefense,
+];
+
+/// Natures in game order. The discriminant is the nature ID.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum Nature {
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+impl Nature {
+    pub const ALL: [Nature; 25] = [
+        Nature::Hardy,
+        Nature::Lonely,
+        Nature::Brave,
+        Nature::Adamant,
+        Nature::Naughty,
+        Nature::Bold,
+        Nature::Docile,
+        Nature::Relaxed,
+        Nature::Impish,
+        Nature::Lax,
+        Nature::Timid,
+        Nature::Hasty,
+        Nature::Serious,
+        Nature::Jolly,
+        Nature::Naive,
+        Nature::Modest,
+        Nature::Mild,
+        Nature::Quiet,
+        Nature::Bashful,
+        Nature::Rash,
+        Nature::Calm,
+        Nature::Gentle,
+        Nature::Sassy,
+        Nature::Careful,
+        Nature::Quirky,
+    ];
+
+    /// Stat raised by 10%, or `None` for neutral natures.
+    pub fn increased_stat(&self) -> Option<Stat> {
+        if self.is_neutral() {
+            None
+        } else {
+            Some(NATURE_STATS[*self as usize / 5])
+        }
+    }
+
+    /// Stat lowered by 10%, or `None` for neutral natures.
+    pub fn decreased_stat(&self) -> Option<Stat> {
+        if self.is_neutral() {
+            None
+        } else {
+            Some(NATURE_STATS[*self as usize % 5])
+        }
+    }
+
+    /// Whether the nature raises and lowers the same stat, leaving all stats unchanged.
+    pub fn is_neutral(&self) -> bool {
+        *self as usize / 5 == *self as usize % 5
+    }
+
+    /// Natures that raise `increased` and lower `decreased`. A `None` stat matches any
+    /// non-neutral nature.
+    pub fn with_modifiers(increased: Option<Stat>, decreased: Option<Stat>) -> Vec<Nature> {
+        Nature::ALL
+            .into_iter()
+            .filter(|n| {
+                !n.is_neutral()
+                    && increased.is_none_or(|s| n.increased_stat() == Some(s))
+                    && decreased.is_none_or(|s| n.decreased_stat() == Some(s))
+            })
+            .collect()
+    }
+
+    pub fn neutral() -> Vec<Nature> {
+        Nature::ALL.into_iter().filter(Nature::is_neutral).collect()
+    }
+
+    /// Parses a nature name or ID, `neutral`, or stat modifiers such as `+Spe -Atk`, `+SpA` or
+    /// `-Spe` into the natures it describes.
+    pub fn parse_group(s: &str) -> Result<Vec<Nature>, String> {
+        let compact = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+
+        if compact.eq_ignore_ascii_case("neutral") {
+            return Ok(Nature::neutral());
+        }
+
+        if !compact.starts_with(['+', '-']) {
+            return s.trim().parse().map(|n| vec![n]);
+        }
+
+        let mut increased = None;
+        let mut decreased = None;
+        let mut rest = compact.as_str();
+        while !rest.is_empty() {
+            let end = rest[1..].find(['+', '-']).map_or(rest.len(), |i| i + 1);
+            let stat = rest[1..end].parse::<Stat>()?;
+            if rest.starts_with('+') {
+                increased = Some(stat);
+            } else {
+                decreased = Some(stat);
+            }
+            rest = &rest[end..];
+        }
+
+        let natures = Nature::with_modifiers(increased, decreased);
+        if natures.is_empty() {
+            Err(format!("No nature matches \"{}\"", s))
+        } else {
+            Ok(natures)
+        }
+    }
+
+    /// English name.
+    pub fn name(&self) -> &'static str {
+        get_text(TextTable::Natures, Language::English, *self as usize).unwrap_or_default()
+    }
+
+    pub fn localized_name<'a>(&self, text: &'a TextTables) -> &'a str {
+        text.get(TextTable::Natures, *self as usize)
+            .unwrap_or_default()
+    }
+}
+
+impl Display for Nature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<u8> for Nature {
+    type Error = GeneratorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Nature::ALL
+            .get(value as usize)
+            .copied()
+            .ok_or(GeneratorError::InvalidNature(value))
+    }
+}
+
+impl FromStr for Nature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse::<u8>() {
+            return Nature::try_from(value).map_err(|e| e.to_string());
+        }
+
+        Nature::ALL
+            .into_iter()
+            .find(|n| n.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Invalid nature \"{}\"", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natures_raise_and_lower_the_right_stats() {
+        for (nature, increased, decreased) in [
+            (Nature::Adamant, Stat::Attack, Stat::SpAttack),
+            (Nature::Brave, Stat::Attack, Stat::Speed),
+            (Nature::Bold, Stat::Defense, Stat::Attack),
+            (Nature::Relaxed, Stat::Defense, Stat::Speed),
+            (Nature::Timid, Stat::Speed, Stat::Attack),
+            (Nature::Jolly, Stat::Speed, Stat::SpAttack),
+            (Nature::Modest, Stat::SpAttack, Stat::Attack),
+            (Nature::Quiet, Stat::SpAttack, Stat::Speed),
+            (Nature::Calm, Stat::SpDefense, Stat::Attack),
+            (Nature::Careful, Stat::SpDefense, Stat::SpAttack),
+        ] {
+            assert_eq!(nature.increased_stat(), Some(increased), "{}", nature);
+            assert_eq!(nature.decreased_stat(), Some(decreased), "{}", nature);
+            assert!(!nature.is_neutral());
+        }
+
+        assert_eq!(
+            Nature::neutral(),
+            [
+                Nature::Hardy,
+                Nature::Docile,
+                Nature::Serious,
+                Nature::Bashful,
+                Nature::Quirky
+            ]
+        );
+        assert_eq!(Nature::Serious.increased_stat(), None);
+        assert_eq!(Nature::Serious.decreased_stat(), None);
+    }
+
+    #[test]
+    fn parse_group_handles_names_ids_and_modifiers() {
+        for (input, natures) in [
+            ("Adamant", vec![Nature::Adamant]),
+            (" jolly ", vec![Nature::Jolly]),
+            ("3", vec![Nature::Adamant]),
+            ("+Spe -Atk", vec![Nature::Timid]),
+            ("-atk+spe", vec![Nature::Timid]),
+            (
+                "+spe",
+                vec![Nature::Timid, Nature::Hasty, Nature::Jolly, Nature::Naive],
+            ),
+            (
+                "-Speed",
+                vec![Nature::Brave, Nature::Relaxed, Nature::Quiet, Nature::Sassy],
+            ),
+            (
+                "Neutral",
+                vec![
+                    Nature::Hardy,
+                    Nature::Docile,
+                    Nature::Serious,
+                    Nature::Bashful,
+                    Nature::Quirky,
+                ],
+            ),
+        ] {
+            assert_eq!(Nature::parse_group(input), Ok(natures), "{}", input);
+        }
+
+        for input in ["25", "Brash", "+Atk-Atk", "+HP", "+Luck", "+"] {
+            assert!(Nature::parse_group(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn natures_convert_from_ids() {
+        for (i, nature) in Nature::ALL.into_iter().enumerate() {
+            assert_eq!(Nature::try_from(i as u8), Ok(nature));
+            assert_eq!(nature.name().parse(), Ok(nature));
+        }
+        assert_eq!(Nature::try_from(25), Err(GeneratorError::InvalidNature(25)));
+    }
+}
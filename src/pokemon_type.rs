@@ -0,0 +1,147 @@
+use crate::resource_util::{get_text, Language, TextTable, TextTables};
+use crate::GeneratorError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Elemental types. The discriminant is the type ID the game tables use.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum PokemonType {
+    Normal,
+    Fighting,
+    Flying,
+    Poison,
+    Ground,
+    Rock,
+    Bug,
+    Ghost,
+    Steel,
+    Fire,
+    Water,
+    Grass,
+    Electric,
+    Psychic,
+    Ice,
+    Dragon,
+    Dark,
+    Fairy,
+}
+
+impl PokemonType {
+    pub const ALL: [PokemonType; 18] = [
+        PokemonType::Normal,
+        PokemonType::Fighting,
+        PokemonType::Flying,
+        PokemonType::Poison,
+        PokemonType::Ground,
+        PokemonType::Rock,
+        PokemonType::Bug,
+        PokemonType::Ghost,
+        PokemonType::Steel,
+        PokemonType::Fire,
+        PokemonType::Water,
+        PokemonType::Grass,
+        PokemonType::Electric,
+        PokemonType::Psychic,
+        PokemonType::Ice,
+        PokemonType::Dragon,
+        PokemonType::Dark,
+        PokemonType::Fairy,
+    ];
+
+    /// Converts a type ID from the game tables, where `-1` marks a missing second type.
+    pub fn from_game_id(id: i8) -> Result<Option<PokemonType>, GeneratorError> {
+        if id == -1 {
+            Ok(None)
+        } else {
+            u8::try_from(id)
+                .map_err(|_| GeneratorError::InvalidType(id))
+                .and_then(PokemonType::try_from)
+                .map(Some)
+        }
+    }
+
+    /// Type ID used by the game tables, with `-1` for no type.
+    pub fn to_game_id(pokemon_type: Option<PokemonType>) -> i8 {
+        pokemon_type.map_or(-1, |t| t as i8)
+    }
+
+    /// English name.
+    pub fn name(&self) -> &'static str {
+        get_text(TextTable::Types, Language::English, *self as usize).unwrap_or_default()
+    }
+
+    pub fn localized_name<'a>(&self, text: &'a TextTables) -> &'a str {
+        text.get(TextTable::Types, *self as usize)
+            .unwrap_or_default()
+    }
+}
+
+impl Display for PokemonType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<u8> for PokemonType {
+    type Error = GeneratorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        PokemonType::ALL
+            .get(value as usize)
+            .copied()
+            .ok_or(GeneratorError::InvalidType(value as i8))
+    }
+}
+
+impl FromStr for PokemonType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse::<u8>() {
+            return PokemonType::try_from(value).map_err(|e| e.to_string());
+        }
+
+        PokemonType::ALL
+            .into_iter()
+            .find(|t| t.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Invalid type \"{}\"", s))
+    }
+}
+
+/// Serde helpers for type IDs stored as `i8` in the game tables.
+pub(crate) mod game_id {
+    use super::PokemonType;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(pokemon_type: &PokemonType, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_i8(*pokemon_type as i8)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<PokemonType, D::Error> {
+        let id = i8::deserialize(d)?;
+        PokemonType::from_game_id(id)
+            .map_err(D::Error::custom)?
+            .ok_or_else(|| D::Error::custom("type ID -1 is only allowed for the second type"))
+    }
+
+    pub mod optional {
+        use super::PokemonType;
+        use serde::de::Error;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            pokemon_type: &Option<PokemonType>,
+            s: S,
+        ) -> Result<S::Ok, S::Error> {
+            s.serialize_i8(PokemonType::to_game_id(*pokemon_type))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            d: D,
+        ) -> Result<Option<PokemonType>, D::Error> {
+            let id = i8::deserialize(d)?;
+            PokemonType::from_game_id(id).map_err(D::Error::custom)
+        }
+    }
+}
@@ -1,3 +1,154 @@
+use crate::personal_info::PersonalInfo;
+use crate::{personal_table, GeneratorError};
+use lazy_static::lazy_static;
+use std::str::FromStr;
+
+const SPECIES_EN_RAW: &str = include_str!("../resources/text/other/en/species_en.txt");
+const FORMS_EN_RAW: &str = include_str!("../resources/text/other/en/forms_en.txt");
+const MOVES_EN_RAW: &str = include_str!("../resources/text/other/en/moves_en.txt");
+const ITEMS_EN_RAW: &str = include_str!("../resources/text/items/items_en.txt");
+const ABILITIES_EN_RAW: &str = include_str!("../resources/text/other/en/abilities_en.txt");
+const NATURES_EN_RAW: &str = include_str!("../resources/text/other/en/natures_en.txt");
+const TYPES_EN_RAW: &str = include_str!("../resources/text/other/en/types_en.txt");
+const CHARACTERISTICS_EN_RAW: &str =
+    include_str!("../resources/text/other/en/characteristics_en.txt");
+
+lazy_static! {
+    pub static ref SPECIES_EN: Vec<&'static str> = load_string_list(SPECIES_EN_RAW);
+    pub static ref FORMS_EN: Vec<&'static str> = load_string_list(FORMS_EN_RAW);
+    pub static ref MOVES_EN: Vec<&'static str> = load_string_list(MOVES_EN_RAW);
+    pub static ref ITEMS_EN: Vec<&'static str> = load_string_list(ITEMS_EN_RAW);
+    pub static ref ABILITIES_EN: Vec<&'static str> = load_string_list(ABILITIES_EN_RAW);
+    pub static ref NATURES_EN: Vec<&'static str> = load_string_list(NATURES_EN_RAW);
+    pub static ref TYPES_EN: Vec<&'static str> = load_string_list(TYPES_EN_RAW);
+    pub static ref CHARACTERISTICS_EN: Vec<&'static str> = load_string_list(CHARACTERISTICS_EN_RAW);
+}
+
+/// Gender symbols indexed by `Pokemon::gender`.
+pub const GENDER_SYMBOLS: [char; 3] = ['♂', '♀', '-'];
+
+/// Languages with embedded text tables.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum Language {
+    #[default]
+    English,
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            _ => Err(format!("Invalid language \"{}\", expected en", s)),
+        }
+    }
+}
+
+/// The kinds of text table.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum TextTable {
+    Species,
+    Forms,
+    Moves,
+    Items,
+    Abilities,
+    Natures,
+    Types,
+    Characteristics,
+}
+
+impl TextTable {
+    pub const ALL: [TextTable; 8] = [
+        TextTable::Species,
+        TextTable::Forms,
+        TextTable::Moves,
+        TextTable::Items,
+        TextTable::Abilities,
+        TextTable::Natures,
+        TextTable::Types,
+        TextTable::Characteristics,
+    ];
+
+    /// Lowercase name, matching the embedded `<name>_en.txt` file names.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextTable::Species => "species",
+            TextTable::Forms => "forms",
+            TextTable::Moves => "moves",
+            TextTable::Items => "items",
+            TextTable::Abilities => "abilities",
+            TextTable::Natures => "natures",
+            TextTable::Types => "types",
+            TextTable::Characteristics => "characteristics",
+        }
+    }
+}
+
+/// The text tables names are looked up in when rendering summaries. Starts out as a language's
+/// embedded tables, and any table can be replaced with one loaded from text, such as another
+/// language's table from PKHeX.
+#[derive(Clone, Debug, Default)]
+pub struct TextTables {
+    language: Language,
+    loaded: [Option<Vec<String>>; 8],
+}
+
+impl TextTables {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            loaded: Default::default(),
+        }
+    }
+
+    /// Replaces `table` with text that has one name per line, in the same order as the embedded
+    /// table. The text must have at least as many lines as the embedded table.
+    pub fn load(&mut self, table: TextTable, text: &str) -> Result<(), GeneratorError> {
+        let lines = load_string_list(text);
+        let expected = get_text_table(table, self.language).len();
+        if lines.len() < expected {
+            return Err(GeneratorError::TableParse {
+                table: table.name(),
+                message: format!("Expected at least {} lines, got {}", expected, lines.len()),
+            });
+        }
+
+        self.loaded[table as usize] = Some(lines.into_iter().map(String::from).collect());
+        Ok(())
+    }
+
+    /// Entry `index` of `table` with surrounding whitespace trimmed, or `None` if the table is
+    /// too short.
+    pub fn get(&self, table: TextTable, index: usize) -> Option<&str> {
+        match &self.loaded[table as usize] {
+            Some(lines) => lines.get(index).map(|s| s.trim()),
+            None => get_text(table, self.language, index),
+        }
+    }
+}
+
+pub fn get_text_table(table: TextTable, language: Language) -> &'static [&'static str] {
+    match language {
+        Language::English => match table {
+            TextTable::Species => &SPECIES_EN,
+            TextTable::Forms => &FORMS_EN,
+            TextTable::Moves => &MOVES_EN,
+            TextTable::Items => &ITEMS_EN,
+            TextTable::Abilities => &ABILITIES_EN,
+            TextTable::Natures => &NATURES_EN,
+            TextTable::Types => &TYPES_EN,
+            TextTable::Characteristics => &CHARACTERISTICS_EN,
+        },
+    }
+}
+
+/// Entry `index` of a text table with surrounding whitespace trimmed, or `None` if the table is
+/// too short.
+pub fn get_text(table: TextTable, language: Language, index: usize) -> Option<&'static str> {
+    get_text_table(table, language).get(index).map(|s| s.trim())
+}
+
 pub fn load_string_list(list: &str) -> Vec<&str> {
     list.split('\n')
         .map(|s| {
@@ -11,3 +162,103 @@ pub fn load_string_list(list: &str) -> Vec<&str> {
         })
         .collect()
 }
+
+/// First alternate form name in `forms_en.txt` for each species with alternate forms.
+/// The base form's name is stored at the species index. Arceus is left out because its forms
+/// are named after types, which `forms_en.txt` doesn't list.
+const ALT_FORM_NAME_INDEXES: [(u16, usize); 10] = [
+    (351, 889),
+    (386, 902),
+    (412, 905),
+    (413, 907),
+    (421, 909),
+    (422, 910),
+    (423, 911),
+    (479, 917),
+    (487, 922),
+    (492, 923),
+];
+
+/// Index of the name of `species` in `form` in `forms_en.txt`, or `None` if the species has no
+/// such form or the form has no name.
+pub fn get_form_name_index(species: u16, form: u8) -> Option<usize> {
+    if form == 0 {
+        Some(species as usize)
+    } else if form as usize
+        >= personal_table::BDSP
+            .get_form_entry(species as usize, 0)
+            .get_form_count()
+    {
+        None
+    } else {
+        ALT_FORM_NAME_INDEXES
+            .iter()
+            .find(|(s, _)| *s == species)
+            .map(|(_, index)| index + form as usize - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form_name(species: u16, form: u8) -> Option<&'static str> {
+        get_form_name_index(species, form)
+            .and_then(|i| get_text(TextTable::Forms, Language::English, i))
+    }
+
+    #[test]
+    fn alternate_form_names_resolve() {
+        assert_eq!(form_name(351, 1), Some("Sunny"));
+        assert_eq!(form_name(386, 3), Some("Speed"));
+        assert_eq!(form_name(413, 2), Some("Trash"));
+        assert_eq!(form_name(422, 1), Some("East"));
+        assert_eq!(form_name(423, 1), Some("East"));
+        assert_eq!(form_name(479, 5), Some("Mow"));
+        assert_eq!(form_name(487, 1), Some("Origin"));
+        assert_eq!(form_name(492, 1), Some("Sky"));
+        assert_eq!(form_name(493, 1), None);
+    }
+
+    #[test]
+    fn loaded_tables_replace_only_their_own_table() {
+        let mut text = TextTables::default();
+        assert_eq!(text.get(TextTable::Natures, 13), Some("Jolly"));
+
+        let natures = NATURES_EN
+            .iter()
+            .map(|n| n.to_uppercase())
+            .collect::<Vec<_>>();
+        text.load(TextTable::Natures, &natures.join("\n")).unwrap();
+        assert_eq!(text.get(TextTable::Natures, 13), Some("JOLLY"));
+        assert_eq!(text.get(TextTable::Natures, 25), None);
+        assert_eq!(text.get(TextTable::Species, 443), Some("Gible"));
+
+        let natures = natures.join("\r\n").to_lowercase();
+        text.load(TextTable::Natures, &natures).unwrap();
+        assert_eq!(text.get(TextTable::Natures, 13), Some("jolly"));
+
+        assert_eq!(
+            text.load(TextTable::Types, "Normal\nFighting"),
+            Err(GeneratorError::TableParse {
+                table: "types",
+                message: format!("Expected at least {} lines, got 2", TYPES_EN.len()),
+            })
+        );
+        assert_eq!(text.get(TextTable::Types, 1), Some("Fighting"));
+        assert_eq!(
+            TextTables::default().get(TextTable::Natures, 13),
+            Some("Jolly")
+        );
+    }
+
+    #[test]
+    fn forms_past_the_form_count_have_no_name() {
+        assert_eq!(form_name(351, 3), Some("Snowy"));
+        assert_eq!(form_name(351, 4), None);
+        assert_eq!(form_name(351, 9), None);
+        assert_eq!(form_name(386, 4), None);
+        assert_eq!(form_name(25, 1), None);
+        assert_eq!(form_name(494, 1), None);
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Whether a Pokemon is shiny, and if so whether it shows stars or squares.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum ShinyType {
+    #[default]
+    None,
+    Star,
+    Square,
+}
+
+impl ShinyType {
+    /// Compares the shiny rand against the PID the same way a trainer's TID/SID is compared.
+    /// A full match gives squares and a match outside the low 4 bits gives stars.
+    pub fn from_pid(shiny_rand: u32, pid: u32) -> ShinyType {
+        let xor = (shiny_rand & 0xFFFF) ^ (shiny_rand >> 0x10) ^ (pid >> 0x10) ^ (pid & 0xFFFF);
+        if xor == 0 {
+            ShinyType::Square
+        } else if xor < 0x10 {
+            ShinyType::Star
+        } else {
+            ShinyType::None
+        }
+    }
+
+    pub fn is_shiny(&self) -> bool {
+        *self != ShinyType::None
+    }
+}
+
+impl Display for ShinyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShinyType::None => write!(f, "No"),
+            ShinyType::Star => write!(f, "Star"),
+            ShinyType::Square => write!(f, "Square"),
+        }
+    }
+}
+
+impl FromStr for ShinyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "no" => Ok(ShinyType::None),
+            "star" => Ok(ShinyType::Star),
+            "square" => Ok(ShinyType::Square),
+            _ => Err(format!(
+                "Invalid shiny type \"{}\", expected none, star or square",
+                s
+            )),
+        }
+    }
+}
+
+/// The kinds of slot a hideaway Pokemon can be generated in.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum SlotKind {
+    Regular,
+    Rare,
+}
+
+/// Sources of extra PID rolls. Every extra roll rerolls the PID once more if the previous
+/// one was not shiny.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ShinyRollConfig {
+    /// Diglett bonus from digging up enough Digletts, which adds one roll and boosts the rates
+    /// of Pokemon with `rateup`.
+    pub diglett: bool,
+    /// Rolls from any other source.
+    pub extra_rolls: u8,
+    /// Whether the extra rolls apply to regular slots.
+    pub regular: bool,
+    /// Whether the extra rolls apply to the rare slot. Off by default, so the rare slot rolls its
+    /// PID once, because rare-slot rerolls have not been checked against in-game captures.
+    pub rare: bool,
+}
+
+impl Default for ShinyRollConfig {
+    fn default() -> Self {
+        Self {
+            diglett: false,
+            extra_rolls: 0,
+            regular: true,
+            rare: false,
+        }
+    }
+}
+
+impl ShinyRollConfig {
+    pub fn diglett(mut self, diglett: bool) -> ShinyRollConfig {
+        self.diglett = diglett;
+        self
+    }
+
+    pub fn extra_rolls(mut self, extra_rolls: u8) -> ShinyRollConfig {
+        self.extra_rolls = extra_rolls;
+        self
+    }
+
+    pub fn slot_kinds(mut self, regular: bool, rare: bool) -> ShinyRollConfig {
+        self.regular = regular;
+        self.rare = rare;
+        self
+    }
+
+    /// Number of times the PID is rolled for a slot of `kind`.
+    pub fn pid_rolls(&self, kind: SlotKind) -> u8 {
+        let applies = match kind {
+            SlotKind::Regular => self.regular,
+            SlotKind::Rare => self.rare,
+        };
+
+        if applies {
+            1u8.saturating_add(self.diglett as u8)
+                .saturating_add(self.extra_rolls)
+        } else {
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_and_square_shinies_are_told_apart() {
+        // TID 12345 and SID 54321 packed the way the shiny rand is, so hi ^ lo is 0xE408.
+        let shiny_rand = (54321 << 16) | 12345;
+        for (pid, shiny_type) in [
+            (0xE4080000, ShinyType::Square),
+            (0x0000E408, ShinyType::Square),
+            (0x1234F63C, ShinyType::Square),
+            (0xE4080001, ShinyType::Star),
+            (0xE408000F, ShinyType::Star),
+            (0x0007E408, ShinyType::Star),
+            (0xE4080010, ShinyType::None),
+            (0xE4180000, ShinyType::None),
+            (0x00000000, ShinyType::None),
+        ] {
+            assert_eq!(
+                ShinyType::from_pid(shiny_rand, pid),
+                shiny_type,
+                "PID {:08X}",
+                pid
+            );
+        }
+    }
+
+    #[test]
+    fn shiny_type_parses_and_displays() {
+        assert_eq!("none".parse(), Ok(ShinyType::None));
+        assert_eq!("No".parse(), Ok(ShinyType::None));
+        assert_eq!("STAR".parse(), Ok(ShinyType::Star));
+        assert_eq!("square".parse(), Ok(ShinyType::Square));
+        assert!("diamond".parse::<ShinyType>().is_err());
+        assert_eq!(ShinyType::Square.to_string(), "Square");
+        assert!(ShinyType::Star.is_shiny());
+        assert!(!ShinyType::None.is_shiny());
+    }
+
+    #[test]
+    fn rare_slot_rolls_once_unless_enabled() {
+        let config = ShinyRollConfig::default();
+        assert_eq!(config.pid_rolls(SlotKind::Regular), 1);
+        assert_eq!(config.pid_rolls(SlotKind::Rare), 1);
+
+        let config = config.diglett(true);
+        assert_eq!(config.pid_rolls(SlotKind::Regular), 2);
+        assert_eq!(config.pid_rolls(SlotKind::Rare), 1);
+
+        let config = config.extra_rolls(2).slot_kinds(true, true);
+        assert_eq!(config.pid_rolls(SlotKind::Regular), 4);
+        assert_eq!(config.pid_rolls(SlotKind::Rare), 4);
+
+        let config = config.slot_kinds(false, true);
+        assert_eq!(config.pid_rolls(SlotKind::Regular), 1);
+    }
+}
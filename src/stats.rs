@@ -0,0 +1,482 @@
+use crate::personal_info::PersonalInfo;
+use crate::{personal_table, GeneratorError, Nature, PokemonType, Species, MAX_SPECIES_ID};
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Shedinja always has 1 HP.
+const SHEDINJA: Species = Species(292);
+
+/// Stats in IV order. The discriminant is the stat's index in IV and stat arrays.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum Stat {
+    Hp,
+    Attack,
+    Defense,
+    SpAttack,
+    SpDefense,
+    Speed,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 6] = [
+        Stat::Hp,
+        Stat::Attack,
+        Stat::Defense,
+        Stat::SpAttack,
+        Stat::SpDefense,
+        Stat::Speed,
+    ];
+
+    /// Position of the stat in IV order.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stat::Hp => write!(f, "HP"),
+            Stat::Attack => write!(f, "Atk"),
+            Stat::Defense => write!(f, "Def"),
+            Stat::SpAttack => write!(f, "SpA"),
+            Stat::SpDefense => write!(f, "SpD"),
+            Stat::Speed => write!(f, "Spe"),
+        }
+    }
+}
+
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace(['-', '_', ' ', '.'], "").to_lowercase();
+        match name.as_str() {
+            "hp" => Ok(Stat::Hp),
+            "atk" | "attack" => Ok(Stat::Attack),
+            "def" | "defense" => Ok(Stat::Defense),
+            "spa" | "spatk" | "specialattack" => Ok(Stat::SpAttack),
+            "spd" | "spdef" | "specialdefense" => Ok(Stat::SpDefense),
+            "spe" | "speed" => Ok(Stat::Speed),
+            _ => Err(format!(
+                "Invalid stat \"{}\", expected one of hp, atk, def, spa, spd, spe",
+                s
+            )),
+        }
+    }
+}
+
+/// Base stats of a species and form in IV order: HP/Atk/Def/SpA/SpD/Spe.
+pub fn base_stats(species: Species, form: u8) -> [u16; 6] {
+    let personal_info = personal_table::BDSP.get_form_entry(species.0 as usize, form as usize);
+    [
+        personal_info.get_hp() as u16,
+        personal_info.get_atk() as u16,
+        personal_info.get_def() as u16,
+        personal_info.get_spa() as u16,
+        personal_info.get_spd() as u16,
+        personal_info.get_spe() as u16,
+    ]
+}
+
+/// Nature multiplier for the stat at `index` in IV order, in tenths: 11, 10 or 9.
+pub fn nature_modifier(nature: Nature, index: usize) -> u16 {
+    if nature.increased_stat().map(|s| s.index()) == Some(index) {
+        11
+    } else if nature.decreased_stat().map(|s| s.index()) == Some(index) {
+        9
+    } else {
+        10
+    }
+}
+
+/// Calculates a single stat. `index` is the stat's position in IV order.
+pub fn calculate_stat(
+    species: Species,
+    index: usize,
+    base: u16,
+    iv: u8,
+    ev: u8,
+    level: u8,
+    nature: Nature,
+) -> u16 {
+    // u32 so levels past 100 don't overflow before the division.
+    let level = level as u32;
+    let scaled = (2 * base as u32 + iv as u32 + ev as u32 / 4) * level / 100;
+    let stat = if index == 0 {
+        if species == SHEDINJA {
+            1
+        } else {
+            scaled + level + 10
+        }
+    } else {
+        (scaled + 5) * nature_modifier(nature, index) as u32 / 10
+    };
+    stat as u16
+}
+
+/// Calculates all six stats in IV order.
+pub fn calculate_stats(
+    species: Species,
+    form: u8,
+    ivs: [u8; 6],
+    evs: [u8; 6],
+    level: u8,
+    nature: Nature,
+) -> [u16; 6] {
+    let base = base_stats(species, form);
+    let mut stats = [0; 6];
+    for (i, stat) in stats.iter_mut().enumerate() {
+        *stat = calculate_stat(species, i, base[i], ivs[i], evs[i], level, nature);
+    }
+    stats
+}
+
+/// IVs that can produce a set of observed stats, in IV order. A stat is `None` when no IV
+/// produces the observed value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IvRanges {
+    pub species: Species,
+    pub form: u8,
+    pub nature: Nature,
+    pub ranges: [Option<RangeInclusive<u8>>; 6],
+}
+
+impl IvRanges {
+    /// Whether every observed stat can be reached by some IV.
+    pub fn is_possible(&self) -> bool {
+        self.ranges.iter().all(|r| r.is_some())
+    }
+
+    pub fn contains(&self, ivs: &[u8; 6]) -> bool {
+        self.ranges
+            .iter()
+            .zip(ivs.iter())
+            .all(|(range, iv)| range.as_ref().is_some_and(|r| r.contains(iv)))
+    }
+}
+
+/// Works out the possible IVs for a Pokemon from the stats shown on its summary screen.
+pub fn iv_ranges_from_stats(
+    species: Species,
+    form: u8,
+    level: u8,
+    nature: Nature,
+    stats: [u16; 6],
+    evs: [u8; 6],
+) -> Result<IvRanges, GeneratorError> {
+    if species.0 == 0 || species.0 > MAX_SPECIES_ID {
+        return Err(GeneratorError::InvalidSpecies(species));
+    }
+    if level == 0 || level > 100 {
+        return Err(GeneratorError::InvalidLevel(level));
+    }
+    if let Some(ev) = evs.iter().find(|ev| **ev > 252) {
+        return Err(GeneratorError::InvalidEv(*ev));
+    }
+
+    let base = base_stats(species, form);
+    let mut ranges: [Option<RangeInclusive<u8>>; 6] = Default::default();
+    for (i, range) in ranges.iter_mut().enumerate() {
+        // Stats never decrease as the IV goes up, so the matching IVs are contiguous.
+        let matches =
+            |iv: &u8| calculate_stat(species, i, base[i], *iv, evs[i], level, nature) == stats[i];
+        if let (Some(min), Some(max)) = ((0..=31u8).find(matches), (0..=31u8).rev().find(matches)) {
+            *range = Some(min..=max);
+        }
+    }
+
+    Ok(IvRanges {
+        species,
+        form,
+        nature,
+        ranges,
+    })
+}
+
+/// Hidden Power has been fixed at 60 base power since Generation VI, and BDSP keeps that.
+pub const HIDDEN_POWER_POWER: u8 = 60;
+
+/// Bit each IV contributes to the Hidden Power type, in IV order. The game weighs the stats in
+/// the order HP, Atk, Def, Spe, SpA, SpD.
+const HIDDEN_POWER_BITS: [u8; 6] = [0, 1, 2, 4, 5, 3];
+
+/// Hidden Power type for a set of IVs. Normal and Fairy can't be rolled, so the result is always
+/// between Fighting and Dark.
+pub fn hidden_power_type(ivs: &[u8; 6]) -> PokemonType {
+    let bits = ivs
+        .iter()
+        .zip(HIDDEN_POWER_BITS)
+        .fold(0u16, |acc, (iv, bit)| acc | (((iv & 1) as u16) << bit));
+    PokemonType::ALL[(bits * 15 / 63) as usize + 1]
+}
+
+/// Rating the IV judge gives a single IV.
+#[derive(PartialEq, Eq, Ord, PartialOrd, Copy, Clone, Debug, Hash)]
+pub enum IvJudge {
+    NoGood,
+    Decent,
+    PrettyGood,
+    VeryGood,
+    Fantastic,
+    Best,
+}
+
+impl IvJudge {
+    pub fn from_iv(iv: u8) -> IvJudge {
+        match iv {
+            0 => IvJudge::NoGood,
+            1..=15 => IvJudge::Decent,
+            16..=25 => IvJudge::PrettyGood,
+            26..=29 => IvJudge::VeryGood,
+            30 => IvJudge::Fantastic,
+            _ => IvJudge::Best,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IvJudge::NoGood => "No Good",
+            IvJudge::Decent => "Decent",
+            IvJudge::PrettyGood => "Pretty Good",
+            IvJudge::VeryGood => "Very Good",
+            IvJudge::Fantastic => "Fantastic",
+            IvJudge::Best => "Best",
+        }
+    }
+}
+
+impl Display for IvJudge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_match_known_values() {
+        // (species, ivs, evs, level, nature, stats)
+        let cases = [
+            // Blissey: 714 HP at level 100 with a 31 IV and 252 EVs.
+            (
+                Species(242),
+                [31; 6],
+                [252, 0, 0, 0, 0, 0],
+                100,
+                Nature::Hardy,
+                [714, 56, 56, 186, 306, 146],
+            ),
+            // Garchomp: 134 Speed at level 50 with a 31 IV, no EVs and Jolly.
+            (
+                Species(445),
+                [31; 6],
+                [0; 6],
+                50,
+                Nature::Jolly,
+                [183, 150, 115, 90, 105, 134],
+            ),
+            // Garchomp: 359 Attack and 333 Speed at level 100 with max investment and Jolly.
+            (
+                Species(445),
+                [31; 6],
+                [0, 252, 0, 0, 0, 252],
+                100,
+                Nature::Jolly,
+                [357, 359, 226, 176, 206, 333],
+            ),
+            // Shedinja always has 1 HP.
+            (
+                Species(292),
+                [31; 6],
+                [252; 6],
+                100,
+                Nature::Adamant,
+                [1, 306, 189, 143, 159, 179],
+            ),
+            (
+                Species(292),
+                [0; 6],
+                [0; 6],
+                1,
+                Nature::Modest,
+                [1, 5, 5, 5, 5, 5],
+            ),
+        ];
+        for (species, ivs, evs, level, nature, stats) in cases {
+            assert_eq!(
+                calculate_stats(species, 0, ivs, evs, level, nature),
+                stats,
+                "species {} level {}",
+                species,
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn levels_past_100_do_not_overflow() {
+        let stats = calculate_stats(Species(242), 0, [31; 6], [252; 6], 150, Nature::Hardy);
+        assert_eq!(stats[0], 1066);
+        let stats = calculate_stats(Species(242), 0, [31; 6], [252; 6], 255, Nature::Hardy);
+        assert_eq!(stats[0], 1540 + 255 + 10);
+    }
+
+    #[test]
+    fn iv_ranges_match_known_values() {
+        // Garchomp at level 50 only moves a stat every other IV.
+        let iv_ranges = iv_ranges_from_stats(
+            Species(445),
+            0,
+            50,
+            Nature::Jolly,
+            [183, 150, 115, 90, 105, 134],
+            [0; 6],
+        )
+        .unwrap();
+        assert_eq!(
+            iv_ranges.ranges,
+            [
+                Some(30..=31),
+                Some(30..=31),
+                Some(30..=31),
+                Some(30..=31),
+                Some(30..=31),
+                Some(30..=31)
+            ]
+        );
+
+        let iv_ranges = iv_ranges_from_stats(
+            Species(242),
+            0,
+            100,
+            Nature::Hardy,
+            [714, 56, 56, 186, 306, 146],
+            [252, 0, 0, 0, 0, 0],
+        )
+        .unwrap();
+        assert_eq!(
+            iv_ranges.ranges,
+            [
+                Some(31..=31),
+                Some(31..=31),
+                Some(31..=31),
+                Some(31..=31),
+                Some(31..=31),
+                Some(31..=31)
+            ]
+        );
+
+        let iv_ranges = iv_ranges_from_stats(
+            Species(445),
+            0,
+            50,
+            Nature::Jolly,
+            [183, 150, 115, 91, 105, 134],
+            [0; 6],
+        )
+        .unwrap();
+        assert!(!iv_ranges.is_possible());
+        assert_eq!(iv_ranges.ranges[3], None);
+    }
+
+    #[test]
+    fn iv_ranges_contain_the_ivs_the_stats_came_from() {
+        let ivs = [
+            [0; 6],
+            [31; 6],
+            [7, 19, 0, 31, 12, 25],
+            [30, 1, 16, 8, 29, 3],
+        ];
+        let evs = [[0; 6], [252, 0, 4, 0, 0, 252], [85; 6]];
+        for species in [1, 129, 242, 292, 445, 493].map(Species) {
+            for nature in [
+                Nature::Hardy,
+                Nature::Adamant,
+                Nature::Modest,
+                Nature::Jolly,
+            ] {
+                for level in [1, 5, 20, 50, 63, 100] {
+                    for ivs in ivs {
+                        for evs in evs {
+                            let stats = calculate_stats(species, 0, ivs, evs, level, nature);
+                            let iv_ranges =
+                                iv_ranges_from_stats(species, 0, level, nature, stats, evs)
+                                    .unwrap();
+                            assert!(
+                                iv_ranges.contains(&ivs),
+                                "species {} level {} ivs {:?} evs {:?}",
+                                species,
+                                level,
+                                ivs,
+                                evs
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iv_ranges_reject_out_of_range_input() {
+        let stats = [714, 56, 56, 186, 306, 146];
+        assert_eq!(
+            iv_ranges_from_stats(Species(0), 0, 100, Nature::Hardy, stats, [0; 6]),
+            Err(GeneratorError::InvalidSpecies(Species(0)))
+        );
+        assert_eq!(
+            iv_ranges_from_stats(Species(242), 0, 0, Nature::Hardy, stats, [0; 6]),
+            Err(GeneratorError::InvalidLevel(0))
+        );
+        assert_eq!(
+            iv_ranges_from_stats(Species(242), 0, 150, Nature::Hardy, stats, [0; 6]),
+            Err(GeneratorError::InvalidLevel(150))
+        );
+        assert_eq!(
+            iv_ranges_from_stats(
+                Species(242),
+                0,
+                100,
+                Nature::Hardy,
+                stats,
+                [0, 0, 253, 0, 0, 0]
+            ),
+            Err(GeneratorError::InvalidEv(253))
+        );
+    }
+
+    #[test]
+    fn hidden_power_type_matches_known_spreads() {
+        // Well-known Hidden Power IV spreads, HP/Atk/Def/SpA/SpD/Spe.
+        for (ivs, expected) in [
+            ([31, 31, 30, 30, 30, 30], PokemonType::Fighting),
+            ([31, 31, 31, 30, 30, 31], PokemonType::Ground),
+            ([31, 30, 31, 30, 31, 30], PokemonType::Fire),
+            ([31, 30, 31, 30, 31, 31], PokemonType::Grass),
+            ([31, 31, 31, 30, 31, 31], PokemonType::Electric),
+            ([31, 30, 30, 31, 31, 31], PokemonType::Ice),
+            ([31; 6], PokemonType::Dark),
+            ([0; 6], PokemonType::Fighting),
+        ] {
+            assert_eq!(hidden_power_type(&ivs), expected, "{:?}", ivs);
+        }
+    }
+
+    #[test]
+    fn nature_modifiers_follow_the_nature_grid() {
+        for index in 0..6 {
+            assert_eq!(nature_modifier(Nature::Hardy, index), 10);
+        }
+        assert_eq!(nature_modifier(Nature::Adamant, 1), 11);
+        assert_eq!(nature_modifier(Nature::Adamant, 3), 9);
+        assert_eq!(nature_modifier(Nature::Jolly, 5), 11);
+        assert_eq!(nature_modifier(Nature::Jolly, 3), 9);
+        assert_eq!(nature_modifier(Nature::Modest, 3), 11);
+        assert_eq!(nature_modifier(Nature::Modest, 1), 9);
+        assert_eq!(nature_modifier(Nature::Modest, 0), 10);
+    }
+}
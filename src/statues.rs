@@ -1,3 +1,4 @@
+use crate::{pokemon_type, GeneratorError, PokemonType};
 use serde::{Deserialize, Serialize};
 
 const RAW_STATUE_DATA: &str = include_str!("../StatueEffectRawData.json");
@@ -16,10 +17,10 @@ pub struct Statue {
     pub rarity: usize,
     pub width: u32,
     pub height: u32,
-    #[serde(rename = "type1Id")]
-    pub type_1_id: i8,
-    #[serde(rename = "type2Id")]
-    pub type_2_id: i8,
+    #[serde(rename = "type1Id", with = "pokemon_type::game_id")]
+    pub type_1: PokemonType,
+    #[serde(rename = "type2Id", with = "pokemon_type::game_id::optional")]
+    pub type_2: Option<PokemonType>,
     #[serde(rename = "pokeTypeEffect")]
     pub poke_type_effect: [u16; 2],
 }
@@ -35,13 +36,24 @@ pub struct StatueEffectRawData {
     table: Vec<Statue>,
 }
 
-pub fn get_statue_data() -> Vec<Statue> {
-    let data = serde_json::from_str::<StatueEffectRawData>(RAW_STATUE_DATA)
-        .expect("Failed to parse raw data");
-    data.table
+pub fn get_statue_data() -> Result<Vec<Statue>, GeneratorError> {
+    let data = serde_json::from_str::<StatueEffectRawData>(RAW_STATUE_DATA).map_err(|e| {
+        GeneratorError::TableParse {
+            table: "StatueEffectRawData",
+            message: e.to_string(),
+        }
+    })?;
+    Ok(data.table)
 }
 
 impl StatueConfig {
+    pub fn from_json(json: &str) -> Result<Self, GeneratorError> {
+        serde_json::from_str(json).map_err(|e| GeneratorError::TableParse {
+            table: "statue config",
+            message: e.to_string(),
+        })
+    }
+
     pub fn get_spawn_count_bonus(&self) -> u32 {
         let mut tiles_used = 0;
         self.statues
@@ -60,19 +72,21 @@ impl StatueConfig {
         }
     }
 
-    pub fn get_bonus_rates(&self) -> [u16; 18] {
-        let mut bonuses = [0; 18];
-
-        self.statues.iter().for_each(|s| {
-            if s.type_1_id != -1 {
-                bonuses[s.type_1_id as usize] += s.poke_type_effect[0];
-            }
-            if s.type_2_id != -1 {
-                bonuses[s.type_2_id as usize] += s.poke_type_effect[1];
-            }
-        });
-
-        bonuses
+    /// Spawn rate bonus the placed statues give to `pokemon_type`.
+    pub fn get_bonus_rate(&self, pokemon_type: PokemonType) -> u16 {
+        self.statues
+            .iter()
+            .map(|s| {
+                let mut bonus = 0;
+                if s.type_1 == pokemon_type {
+                    bonus += s.poke_type_effect[0];
+                }
+                if s.type_2 == Some(pokemon_type) {
+                    bonus += s.poke_type_effect[1];
+                }
+                bonus
+            })
+            .sum()
     }
 
     pub fn add_statue(&mut self, statue: Statue) {
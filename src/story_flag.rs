@@ -0,0 +1,83 @@
+use crate::GeneratorError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Story progress milestones that unlock new hideaway encounters. The discriminant is the
+/// `zukanflag` value used by the encounter tables.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub enum StoryFlag {
+    UndergroundUnlocked = 1,
+    Strength,
+    Defog,
+    SevenBadges,
+    Waterfall,
+    NationalDex,
+}
+
+impl StoryFlag {
+    pub const ALL: [StoryFlag; 6] = [
+        StoryFlag::UndergroundUnlocked,
+        StoryFlag::Strength,
+        StoryFlag::Defog,
+        StoryFlag::SevenBadges,
+        StoryFlag::Waterfall,
+        StoryFlag::NationalDex,
+    ];
+
+    /// The in-game event that unlocks this milestone.
+    pub fn description(&self) -> &'static str {
+        match self {
+            StoryFlag::UndergroundUnlocked => {
+                "Received the Explorer Kit and unlocked the Grand Underground"
+            }
+            StoryFlag::Strength => "Obtained HM Strength",
+            StoryFlag::Defog => "Obtained HM Defog",
+            StoryFlag::SevenBadges => "Obtained the seventh Gym Badge",
+            StoryFlag::Waterfall => "Obtained HM Waterfall",
+            StoryFlag::NationalDex => "Obtained the National Pokedex",
+        }
+    }
+
+    /// Index into per-story-flag tables such as `flagrate`.
+    pub(crate) fn index(&self) -> usize {
+        *self as usize - 1
+    }
+}
+
+impl Display for StoryFlag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", *self as u8, self.description())
+    }
+}
+
+impl TryFrom<u8> for StoryFlag {
+    type Error = GeneratorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        StoryFlag::ALL
+            .into_iter()
+            .find(|flag| *flag as u8 == value)
+            .ok_or(GeneratorError::InvalidStoryFlag(value))
+    }
+}
+
+impl FromStr for StoryFlag {
+    type Err = GeneratorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse::<u8>() {
+            return StoryFlag::try_from(value);
+        }
+
+        let name = s.replace(['-', '_', ' '], "").to_lowercase();
+        match name.as_str() {
+            "undergroundunlocked" | "explorerkit" => Ok(StoryFlag::UndergroundUnlocked),
+            "strength" => Ok(StoryFlag::Strength),
+            "defog" => Ok(StoryFlag::Defog),
+            "sevenbadges" | "7badges" => Ok(StoryFlag::SevenBadges),
+            "waterfall" => Ok(StoryFlag::Waterfall),
+            "nationaldex" => Ok(StoryFlag::NationalDex),
+            _ => Err(GeneratorError::UnknownStoryFlag(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::resource_util::TextTables;
+use crate::{Advance, Pokemon, Stat};
+use std::fmt::{Display, Formatter};
+
+/// Layout used when rendering a `Pokemon` or `Advance` as text.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum SummaryFormat {
+    /// One line per Pokemon. Advances prefix each line with the advance number.
+    OneLine,
+    /// The block layout printed by the command line tool.
+    #[default]
+    MultiLine,
+}
+
+/// Text rendering of a `Pokemon`, created by `Pokemon::summary`.
+pub struct PokemonSummary<'a> {
+    pokemon: &'a Pokemon,
+    format: SummaryFormat,
+    text: &'a TextTables,
+}
+
+/// Text rendering of an `Advance`, created by `Advance::summary`.
+pub struct AdvanceSummary<'a> {
+    advance: &'a Advance,
+    format: SummaryFormat,
+    text: &'a TextTables,
+}
+
+impl Pokemon {
+    /// Renders the pokemon in `format`, looking names up in `text`.
+    pub fn summary<'a>(
+        &'a self,
+        format: SummaryFormat,
+        text: &'a TextTables,
+    ) -> PokemonSummary<'a> {
+        PokemonSummary {
+            pokemon: self,
+            format,
+            text,
+        }
+    }
+}
+
+impl Advance {
+    /// Renders the advance in `format`, looking names up in `text`.
+    pub fn summary<'a>(
+        &'a self,
+        format: SummaryFormat,
+        text: &'a TextTables,
+    ) -> AdvanceSummary<'a> {
+        AdvanceSummary {
+            advance: self,
+            format,
+            text,
+        }
+    }
+}
+
+impl Display for PokemonSummary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let pokemon = self.pokemon;
+        let text = self.text;
+        let species = pokemon.species.localized_name(text).unwrap_or_default();
+        let form = pokemon
+            .form_name(text)
+            .map(|form| format!("-{}", form))
+            .unwrap_or_default();
+        let ability = pokemon
+            .ability_id()
+            .localized_name(text)
+            .unwrap_or_default();
+        let item = pokemon.item.localized_name(text).unwrap_or_default();
+        let egg_move = pokemon.egg_move.and_then(|m| m.localized_name(text));
+        // Levels are opt-in, and stats need a level.
+        let stats = pokemon.level.map(|level| pokemon.stats(level));
+
+        match self.format {
+            SummaryFormat::OneLine => write!(
+                f,
+                "{}{}{} | PID: {:08X} EC: {:08X} Shiny: {} | IVs: {} | Judge: {}{} | Hidden Power: {} | {} | {} | {} | {} | Item: {}{} | Height: {} Weight: {}",
+                species,
+                form,
+                pokemon
+                    .level
+                    .map(|level| format!(" Lv. {}", level))
+                    .unwrap_or_default(),
+                pokemon.pid,
+                pokemon.ec,
+                pokemon.shiny,
+                join_slash(&pokemon.ivs),
+                join_slash(&pokemon.iv_judges()),
+                stats
+                    .map(|stats| format!(" | Stats: {}", join_slash(&stats)))
+                    .unwrap_or_default(),
+                pokemon.hidden_power_type().localized_name(text),
+                ability,
+                pokemon.gender_symbol(),
+                pokemon.characteristic().localized_name(text),
+                pokemon.nature.localized_name(text),
+                item,
+                egg_move
+                    .map(|m| format!(" | Egg Move: {}", m))
+                    .unwrap_or_default(),
+                pokemon.height,
+                pokemon.weight
+            ),
+            SummaryFormat::MultiLine => write!(
+                f,
+                "Species: {}{}{}\nPID: {:08X} EC: {:08X} Shiny: {}\nIVs: {:?}{} Hidden Power: {}\nJudge: {}\nAbility: {} Gender: {} Characteristic: {}\nNature: {} Item: {}{}\nHeight: {} ({:.2}m) Weight: {} ({:.1}kg)",
+                species,
+                form,
+                pokemon
+                    .level
+                    .map(|level| format!(" Level: {}", level))
+                    .unwrap_or_default(),
+                pokemon.pid,
+                pokemon.ec,
+                pokemon.shiny,
+                pokemon.ivs,
+                stats
+                    .map(|stats| format!(" Stats: {:?}", stats))
+                    .unwrap_or_default(),
+                pokemon.hidden_power_type().localized_name(text),
+                judges(pokemon),
+                ability,
+                pokemon.gender_symbol(),
+                pokemon.characteristic().localized_name(text),
+                pokemon.nature.localized_name(text),
+                item,
+                egg_move
+                    .map(|m| format!(" Egg Move: {}", m))
+                    .unwrap_or_default(),
+                pokemon.height,
+                pokemon.height_absolute(),
+                pokemon.weight,
+                pokemon.weight_absolute()
+            ),
+        }
+    }
+}
+
+impl Display for AdvanceSummary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.format {
+            SummaryFormat::OneLine => {
+                for (i, pokemon) in self.advance.pokemon().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(
+                        f,
+                        "{} | {}",
+                        self.advance.advance,
+                        pokemon.summary(self.format, self.text)
+                    )?;
+                }
+                Ok(())
+            }
+            SummaryFormat::MultiLine => {
+                write!(
+                    f,
+                    "-------------------------------------------\nAdvances: {}",
+                    self.advance.advance
+                )?;
+                for (i, pokemon) in self.advance.pokemon().enumerate() {
+                    let separator = if i == 0 { "\n" } else { "\n\n" };
+                    write!(
+                        f,
+                        "{}{}",
+                        separator,
+                        pokemon.summary(self.format, self.text)
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Multi-line English summary.
+impl Display for Pokemon {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.summary(SummaryFormat::default(), &TextTables::default())
+            .fmt(f)
+    }
+}
+
+/// Multi-line English summary.
+impl Display for Advance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.summary(SummaryFormat::default(), &TextTables::default())
+            .fmt(f)
+    }
+}
+
+/// IV judge ratings labelled by stat, e.g. `HP Best, Atk No Good, ...`.
+fn judges(pokemon: &Pokemon) -> String {
+    Stat::ALL
+        .iter()
+        .zip(pokemon.iv_judges())
+        .map(|(stat, judge)| format!("{} {}", stat, judge))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn join_slash<T: Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join("/")
+}
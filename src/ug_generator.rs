@@ -0,0 +1,221 @@
+use crate::xorshift::XorShift;
+use crate::{Advance, Filter, GeneratorContext, GeneratorError, PokemonFilter};
+
+/// Lazily generates advances `initial_advance..=initial_advance + advances`, yielding only
+/// those that pass the filter.
+pub struct UgGenerator<'a, F: PokemonFilter + ?Sized = Filter> {
+    context: &'a GeneratorContext,
+    filter: &'a F,
+    rng: XorShift,
+    advance: u32,
+    max_advance: u32,
+    finished: bool,
+}
+
+impl<'a, F: PokemonFilter + ?Sized> UgGenerator<'a, F> {
+    /// Fails if the last advance, `initial_advance + advances`, does not fit in a `u32`.
+    pub fn new(
+        context: &'a GeneratorContext,
+        mut rng: XorShift,
+        initial_advance: u32,
+        advances: u32,
+        filter: &'a F,
+    ) -> Result<Self, GeneratorError> {
+        let max_advance = last_advance(initial_advance, advances)?;
+        rng.jump(initial_advance as usize);
+        Ok(Self {
+            context,
+            filter,
+            rng,
+            advance: initial_advance,
+            max_advance,
+            finished: false,
+        })
+    }
+
+    /// Generates `start..=end`, where `rng` is already at the state for `start`.
+    pub(crate) fn with_range(
+        context: &'a GeneratorContext,
+        rng: XorShift,
+        start: u32,
+        end: u32,
+        filter: &'a F,
+    ) -> Self {
+        Self {
+            context,
+            filter,
+            rng,
+            advance: start,
+            max_advance: end,
+            finished: start > end,
+        }
+    }
+
+    /// The advance the next call to `next` will generate first.
+    pub fn current_advance(&self) -> u32 {
+        self.advance
+    }
+}
+
+impl<F: PokemonFilter + ?Sized> Iterator for UgGenerator<'_, F> {
+    type Item = Advance;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let advance = self.context.generate_advance(self.advance, self.rng);
+
+            self.rng.next();
+            if self.advance == self.max_advance {
+                self.finished = true;
+            } else {
+                self.advance += 1;
+            }
+
+            if let Some(advance) = self.filter.apply(advance) {
+                return Some(advance);
+            }
+        }
+
+        None
+    }
+}
+
+/// Last advance of a search window starting at `initial_advance`.
+pub(crate) fn last_advance(initial_advance: u32, advances: u32) -> Result<u32, GeneratorError> {
+    initial_advance
+        .checked_add(advances)
+        .ok_or(GeneratorError::AdvanceOverflow {
+            initial_advance,
+            advances,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statues::StatueConfig;
+    use crate::{RoomType, ShinyRollConfig, Species, StoryFlag, Version};
+
+    fn rng() -> XorShift {
+        XorShift::from_state([0x12345678, 0x9ABCDEF0, 0x0FEDCBA9, 0x87654321])
+    }
+
+    fn context() -> GeneratorContext {
+        GeneratorContext::new(
+            Version::BD,
+            StoryFlag::NationalDex,
+            RoomType::SpaciousCave,
+            &StatueConfig::default(),
+            ShinyRollConfig::default(),
+        )
+        .unwrap()
+    }
+
+    fn pass_all() -> Filter {
+        Filter::default().max_ivs([31; 6])
+    }
+
+    fn advances(results: &[Advance]) -> Vec<u32> {
+        results.iter().map(|a| a.advance).collect()
+    }
+
+    #[test]
+    fn yields_every_advance_then_stops() {
+        let context = context();
+        let filter = pass_all();
+        let mut generator = UgGenerator::new(&context, rng(), 0, 5, &filter).unwrap();
+
+        let results = generator.by_ref().collect::<Vec<Advance>>();
+        assert_eq!(advances(&results), [0, 1, 2, 3, 4, 5]);
+        assert!(generator.next().is_none());
+    }
+
+    #[test]
+    fn take_stops_generating_early() {
+        let context = context();
+        let filter = pass_all();
+        let mut generator = UgGenerator::new(&context, rng(), 0, u32::MAX, &filter).unwrap();
+
+        let results = generator.by_ref().take(3).collect::<Vec<Advance>>();
+        assert_eq!(advances(&results), [0, 1, 2]);
+        assert_eq!(generator.current_advance(), 3);
+    }
+
+    #[test]
+    fn find_matches_the_collected_results() {
+        let context = context();
+        let filter = pass_all();
+        let expected = context
+            .run_results(0, 1000, rng(), &filter)
+            .unwrap()
+            .into_iter()
+            .find(|a| a.rare_pokemon.is_some())
+            .unwrap();
+
+        let mut generator = UgGenerator::new(&context, rng(), 0, u32::MAX, &filter).unwrap();
+        let found = generator.find(|a| a.rare_pokemon.is_some()).unwrap();
+        assert_eq!(found.advance, expected.advance);
+        assert_eq!(generator.current_advance(), found.advance + 1);
+    }
+
+    #[test]
+    fn filtered_advances_match_run_results() {
+        let context = context();
+        let filter = pass_all().species(vec![Species(81)]);
+        let expected = context.run_results(0, 2000, rng(), &filter).unwrap();
+        assert!(!expected.is_empty());
+
+        let results = UgGenerator::new(&context, rng(), 0, 2000, &filter)
+            .unwrap()
+            .collect::<Vec<Advance>>();
+        assert_eq!(advances(&results), advances(&expected));
+    }
+
+    #[test]
+    fn initial_advance_skips_earlier_advances() {
+        let context = context();
+        let filter = pass_all();
+        let expected = UgGenerator::new(&context, rng(), 0, 1010, &filter)
+            .unwrap()
+            .skip(1000)
+            .collect::<Vec<Advance>>();
+
+        let results = UgGenerator::new(&context, rng(), 1000, 10, &filter)
+            .unwrap()
+            .collect::<Vec<Advance>>();
+        assert_eq!(advances(&results), advances(&expected));
+        assert_eq!(
+            results
+                .iter()
+                .map(|a| a.regular_pokemon[0].pid)
+                .collect::<Vec<u32>>(),
+            expected
+                .iter()
+                .map(|a| a.regular_pokemon[0].pid)
+                .collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    fn window_can_end_at_u32_max() {
+        let context = context();
+        let filter = pass_all();
+        let mut generator = UgGenerator::new(&context, rng(), u32::MAX - 2, 2, &filter).unwrap();
+
+        let results = generator.by_ref().collect::<Vec<Advance>>();
+        assert_eq!(advances(&results), [u32::MAX - 2, u32::MAX - 1, u32::MAX]);
+        assert!(generator.next().is_none());
+    }
+
+    #[test]
+    fn last_advance_rejects_windows_past_u32_max() {
+        assert_eq!(last_advance(u32::MAX - 10, 10), Ok(u32::MAX));
+        assert_eq!(
+            last_advance(4294967290, 10),
+            Err(GeneratorError::AdvanceOverflow {
+                initial_advance: 4294967290,
+                advances: 10
+            })
+        );
+    }
+}